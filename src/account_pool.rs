@@ -0,0 +1,357 @@
+//! Several trading accounts behind one transport
+//!
+//! An [`AccountPool`] holds one [`ClobClient`] per wallet, each with its own
+//! signer, API credentials and funder, all built with
+//! [`ClobClientBuilder::shared_with`] so they reuse a single connection pool,
+//! DNS cache, buffer pool, market metadata cache and rate limiter. Orders and
+//! cancels are routed by account label; open orders and balances can be read
+//! across every account at once.
+
+use crate::client::{ClobClient, ClobClientBuilder, OrderArgs};
+use crate::errors::{PolyfillError, Result};
+use crate::types::{
+    BalanceAllowanceParams, CancelResponse, OpenOrder, OpenOrderParams, OrderType,
+    PostOrderResponse, SignedOrderRequest,
+};
+use futures::future::{join_all, try_join_all};
+use rust_decimal::Decimal;
+use serde_json::Value;
+use std::str::FromStr;
+
+/// An open order and the account it belongs to
+#[derive(Debug, Clone)]
+pub struct AccountOrder {
+    pub label: String,
+    pub order: OpenOrder,
+}
+
+/// A balance reported for one account
+#[derive(Debug, Clone)]
+pub struct AccountBalance {
+    pub label: String,
+    /// Balance in base units, as reported by the exchange
+    pub balance: Decimal,
+    /// The full `GET /balance-allowance` response, including allowances
+    pub details: Value,
+}
+
+/// Trading accounts sharing one transport, addressed by label
+pub struct AccountPool {
+    transport: ClobClient,
+    accounts: Vec<(String, ClobClient)>,
+}
+
+impl AccountPool {
+    /// Create an empty pool around `transport`
+    ///
+    /// `transport` owns the shared connection pool, caches and rate limiter,
+    /// and serves market data reads. It needs no credentials.
+    pub fn new(transport: ClobClient) -> Self {
+        Self {
+            transport,
+            accounts: Vec::new(),
+        }
+    }
+
+    /// Client used for market data and as the source of shared state
+    pub fn transport(&self) -> &ClobClient {
+        &self.transport
+    }
+
+    /// Builder for an account on the pool's host and chain
+    ///
+    /// Set the account's key, credentials and funder, then hand it to
+    /// [`AccountPool::add_account`].
+    pub fn account_builder(&self) -> ClobClientBuilder {
+        ClobClient::builder(&self.transport.base_url).chain_id(self.transport.chain_id())
+    }
+
+    /// Build `builder` on the shared transport and register it as `label`
+    ///
+    /// Fails if the label is taken or the client cannot be built.
+    pub fn add_account(
+        &mut self,
+        label: impl Into<String>,
+        builder: ClobClientBuilder,
+    ) -> Result<&ClobClient> {
+        let label = label.into();
+        if self.get(&label).is_some() {
+            return Err(PolyfillError::config(format!(
+                "Account {} is already in the pool",
+                label
+            )));
+        }
+
+        let client = builder.shared_with(&self.transport).build()?;
+        self.accounts.push((label, client));
+        Ok(&self.accounts[self.accounts.len() - 1].1)
+    }
+
+    /// Remove an account, returning its client
+    pub fn remove_account(&mut self, label: &str) -> Option<ClobClient> {
+        let index = self.accounts.iter().position(|(name, _)| name == label)?;
+        Some(self.accounts.remove(index).1)
+    }
+
+    /// Client of the account registered as `label`
+    pub fn get(&self, label: &str) -> Option<&ClobClient> {
+        self.accounts
+            .iter()
+            .find(|(name, _)| name == label)
+            .map(|(_, client)| client)
+    }
+
+    /// Client of the account registered as `label`, or a config error
+    pub fn account(&self, label: &str) -> Result<&ClobClient> {
+        self.get(label)
+            .ok_or_else(|| PolyfillError::config(format!("Unknown account: {}", label)))
+    }
+
+    /// Account labels, in the order they were added
+    pub fn labels(&self) -> impl Iterator<Item = &str> + '_ {
+        self.accounts.iter().map(|(label, _)| label.as_str())
+    }
+
+    pub fn len(&self) -> usize {
+        self.accounts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.accounts.is_empty()
+    }
+
+    /// Create, sign and post a GTC order from `label`
+    pub async fn create_and_post_order(
+        &self,
+        label: &str,
+        order_args: &OrderArgs,
+    ) -> Result<PostOrderResponse> {
+        self.account(label)?.create_and_post_order(order_args).await
+    }
+
+    /// Post an order signed by `label`
+    pub async fn post_order(
+        &self,
+        label: &str,
+        order: SignedOrderRequest,
+        order_type: OrderType,
+    ) -> Result<PostOrderResponse> {
+        self.account(label)?.post_order(order, order_type).await
+    }
+
+    pub async fn cancel(&self, label: &str, order_id: &str) -> Result<CancelResponse> {
+        self.account(label)?.cancel(order_id).await
+    }
+
+    pub async fn cancel_orders(&self, label: &str, order_ids: &[String]) -> Result<CancelResponse> {
+        self.account(label)?.cancel_orders(order_ids).await
+    }
+
+    pub async fn cancel_all(&self, label: &str) -> Result<CancelResponse> {
+        self.account(label)?.cancel_all().await
+    }
+
+    /// Cancel every open order of every account, per label
+    ///
+    /// Every account is attempted even if others fail, so one rejected cancel
+    /// never leaves the remaining accounts' orders resting.
+    pub async fn cancel_all_accounts(&self) -> Vec<(String, Result<CancelResponse>)> {
+        join_all(
+            self.accounts
+                .iter()
+                .map(|(label, client)| async move { (label.clone(), client.cancel_all().await) }),
+        )
+        .await
+    }
+
+    /// Open orders of every account, fetched concurrently
+    ///
+    /// Fails as soon as any account's request fails.
+    pub async fn open_orders(&self, params: Option<&OpenOrderParams>) -> Result<Vec<AccountOrder>> {
+        let per_account = try_join_all(self.accounts.iter().map(|(label, client)| async move {
+            let orders = client.get_orders(params, None).await?;
+            Ok::<_, PolyfillError>(orders.into_iter().map(|order| AccountOrder {
+                label: label.clone(),
+                order,
+            }))
+        }))
+        .await?;
+
+        Ok(per_account.into_iter().flatten().collect())
+    }
+
+    /// Balance of every account for the asset described by `params`
+    ///
+    /// Fails as soon as any account's request fails, so totals never silently
+    /// leave an account out.
+    pub async fn balances(&self, params: &BalanceAllowanceParams) -> Result<Vec<AccountBalance>> {
+        try_join_all(self.accounts.iter().map(|(label, client)| async move {
+            let details = client.get_balance_allowance(Some(params.clone())).await?;
+            let balance = parse_balance(&details)?;
+            Ok(AccountBalance {
+                label: label.clone(),
+                balance,
+                details,
+            })
+        }))
+        .await
+    }
+
+    /// Sum of [`AccountPool::balances`], in base units
+    pub async fn total_balance(&self, params: &BalanceAllowanceParams) -> Result<Decimal> {
+        Ok(self
+            .balances(params)
+            .await?
+            .iter()
+            .map(|account| account.balance)
+            .sum())
+    }
+}
+
+/// Read the `balance` field, sent as a string or a number
+fn parse_balance(details: &Value) -> Result<Decimal> {
+    let balance = match details.get("balance") {
+        Some(Value::String(balance)) => Decimal::from_str(balance).ok(),
+        Some(Value::Number(balance)) => Decimal::from_str(&balance.to_string()).ok(),
+        _ => None,
+    };
+    balance.ok_or_else(|| {
+        PolyfillError::parse(format!("Missing or invalid balance in {}", details), None)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::ApiCredentials;
+    use mockito::{Matcher, Server};
+    use std::sync::Arc;
+
+    fn credentials(api_key: &str) -> ApiCredentials {
+        ApiCredentials {
+            api_key: api_key.to_string(),
+            secret: "dGVzdF9zZWNyZXRfa2V5XzEyMzQ1".to_string(),
+            passphrase: "test_passphrase".to_string(),
+        }
+    }
+
+    fn open_order_json(id: &str) -> String {
+        format!(
+            r#"{{"associate_trades": [], "id": "{}", "status": "LIVE", "market": "0xm",
+                "original_size": "10", "outcome": "Yes", "maker_address": "0x1", "owner": "o",
+                "price": "0.5", "side": "BUY", "size_matched": "0", "asset_id": "123",
+                "expiration": "0", "type": "GTC", "created_at": "1700000000"}}"#,
+            id
+        )
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_pool_routes_by_label_and_aggregates() {
+        let mut server = Server::new_async().await;
+        for (api_key, order_id, balance) in [("key_a", "0xa", "5000000"), ("key_b", "0xb", "7")] {
+            server
+                .mock("GET", "/data/orders")
+                .match_header("poly_api_key", api_key)
+                .match_query(Matcher::Any)
+                .with_body(format!(
+                    r#"{{"data": [{}], "next_cursor": "LTE="}}"#,
+                    open_order_json(order_id)
+                ))
+                .create_async()
+                .await;
+            server
+                .mock("GET", "/balance-allowance")
+                .match_header("poly_api_key", api_key)
+                .match_query(Matcher::Any)
+                .with_body(format!(
+                    r#"{{"balance": "{}", "allowances": {{}}}}"#,
+                    balance
+                ))
+                .create_async()
+                .await;
+        }
+        let cancel = server
+            .mock("DELETE", "/order")
+            .match_header("poly_api_key", "key_b")
+            .with_body(r#"{"canceled": ["0xb"], "not_canceled": {}}"#)
+            .create_async()
+            .await;
+
+        let mut pool = AccountPool::new(ClobClient::new_internet(&server.url()));
+        for (label, key, api_key) in [
+            (
+                "alpha",
+                "0x1111111111111111111111111111111111111111111111111111111111111111",
+                "key_a",
+            ),
+            (
+                "beta",
+                "0x2222222222222222222222222222222222222222222222222222222222222222",
+                "key_b",
+            ),
+        ] {
+            let builder = pool
+                .account_builder()
+                .private_key(key)
+                .api_creds(credentials(api_key));
+            pool.add_account(label, builder).unwrap();
+        }
+        assert!(pool.add_account("alpha", pool.account_builder()).is_err());
+        assert_eq!(pool.labels().collect::<Vec<_>>(), vec!["alpha", "beta"]);
+
+        let alpha = pool.account("alpha").unwrap();
+        assert!(Arc::ptr_eq(
+            alpha.market_cache(),
+            pool.transport().market_cache()
+        ));
+        assert!(Arc::ptr_eq(
+            alpha.rate_limiter().unwrap(),
+            pool.transport().rate_limiter().unwrap()
+        ));
+        assert!(!Arc::ptr_eq(
+            alpha.order_registry(),
+            pool.get("beta").unwrap().order_registry()
+        ));
+
+        let orders = pool.open_orders(None).await.unwrap();
+        let owners: Vec<_> = orders
+            .iter()
+            .map(|entry| (entry.label.as_str(), entry.order.id.as_str()))
+            .collect();
+        assert_eq!(owners, vec![("alpha", "0xa"), ("beta", "0xb")]);
+
+        let params = BalanceAllowanceParams::default();
+        assert_eq!(
+            pool.total_balance(&params).await.unwrap(),
+            Decimal::from(5_000_007)
+        );
+
+        let response = pool.cancel("beta", "0xb").await.unwrap();
+        assert!(response.is_canceled("0xb"));
+        cancel.assert_async().await;
+        assert!(pool.cancel("gamma", "0xb").await.is_err());
+
+        // A rejected cancel for one account still cancels the others
+        let cancel_a = server
+            .mock("DELETE", "/cancel-all")
+            .match_header("poly_api_key", "key_a")
+            .with_status(400)
+            .with_body(r#"{"error": "rejected"}"#)
+            .create_async()
+            .await;
+        let cancel_b = server
+            .mock("DELETE", "/cancel-all")
+            .match_header("poly_api_key", "key_b")
+            .with_body(r#"{"canceled": ["0xb"], "not_canceled": {}}"#)
+            .create_async()
+            .await;
+        let results = pool.cancel_all_accounts().await;
+        cancel_a.assert_async().await;
+        cancel_b.assert_async().await;
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].0, "alpha");
+        assert!(results[0].1.is_err());
+        assert_eq!(results[1].0, "beta");
+        assert!(results[1].1.as_ref().unwrap().is_canceled("0xb"));
+    }
+}
@@ -5,15 +5,16 @@
 
 use crate::errors::{PolyfillError, Result};
 use crate::types::ApiCredentials;
-use alloy_primitives::{hex::encode_prefixed, Address, U256};
+use alloy_primitives::{hex::encode_prefixed, Address, PrimitiveSignature, B256, U256};
 use alloy_signer::SignerSync;
 use alloy_signer_local::PrivateKeySigner;
-use alloy_sol_types::{eip712_domain, sol};
+use alloy_sol_types::{eip712_domain, sol, Eip712Domain, SolStruct};
 use base64::engine::Engine;
 use hmac::{Hmac, Mac};
 use serde::Serialize;
 use sha2::Sha256;
 use std::collections::HashMap;
+use std::str::FromStr;
 use std::time::{SystemTime, UNIX_EPOCH};
 
 // Header constants
@@ -62,35 +63,79 @@ pub fn get_current_unix_time_secs() -> u64 {
         .as_secs()
 }
 
+/// Statement an L1 signature attests to
+const CLOB_AUTH_MESSAGE: &str = "This message attests that I control the given wallet";
+
+/// EIP-712 domain of L1 authentication messages, always on Polygon
+fn clob_auth_domain() -> Eip712Domain {
+    let polygon = 137;
+    eip712_domain!(
+        name: "ClobAuthDomain",
+        version: "1",
+        chain_id: polygon,
+    )
+}
+
 /// Sign CLOB authentication message using EIP-712
 pub fn sign_clob_auth_message(
     signer: &PrivateKeySigner,
     timestamp: String,
     nonce: U256,
 ) -> Result<String> {
-    let message = "This message attests that I control the given wallet".to_string();
-    let polygon = 137;
-
     let auth_struct = ClobAuth {
         address: signer.address(),
         timestamp,
         nonce,
-        message,
+        message: CLOB_AUTH_MESSAGE.to_string(),
     };
 
-    let domain = eip712_domain!(
-        name: "ClobAuthDomain",
-        version: "1",
-        chain_id: polygon,
-    );
-
     let signature = signer
-        .sign_typed_data_sync(&auth_struct, &domain)
+        .sign_typed_data_sync(&auth_struct, &clob_auth_domain())
         .map_err(|e| PolyfillError::crypto(format!("EIP-712 signature failed: {}", e)))?;
 
     Ok(encode_prefixed(signature.as_bytes()))
 }
 
+/// Recover the address that signed an L1 authentication message
+///
+/// The headers are valid when the recovered address is `address` itself.
+pub fn recover_clob_auth_signer(
+    address: Address,
+    timestamp: &str,
+    nonce: U256,
+    signature: &str,
+) -> Result<Address> {
+    let auth_struct = ClobAuth {
+        address,
+        timestamp: timestamp.to_string(),
+        nonce,
+        message: CLOB_AUTH_MESSAGE.to_string(),
+    };
+    let hash = auth_struct.eip712_signing_hash(&clob_auth_domain());
+
+    PrimitiveSignature::from_str(signature)
+        .and_then(|signature| signature.recover_address_from_prehash(&hash))
+        .map_err(|e| PolyfillError::crypto(format!("Invalid L1 signature: {}", e)))
+}
+
+/// EIP-712 domain of the exchange contract that settles orders
+fn order_domain(chain_id: u64, verifying_contract: Address) -> Eip712Domain {
+    eip712_domain!(
+        name: "Polymarket CTF Exchange",
+        version: "1",
+        chain_id: chain_id,
+        verifying_contract: verifying_contract,
+    )
+}
+
+/// Compute the EIP-712 hash of an order
+///
+/// The exchange uses this hash as the order ID, so it is known before the
+/// order is posted.
+pub fn order_hash(order: &Order, chain_id: u64, verifying_contract: Address) -> B256 {
+    order.eip712_signing_hash(&order_domain(chain_id, verifying_contract))
+}
+
 /// Sign order message using EIP-712
 pub fn sign_order_message(
     signer: &PrivateKeySigner,
@@ -98,12 +143,7 @@ pub fn sign_order_message(
     chain_id: u64,
     verifying_contract: Address,
 ) -> Result<String> {
-    let domain = eip712_domain!(
-        name: "Polymarket CTF Exchange",
-        version: "1",
-        chain_id: chain_id,
-        verifying_contract: verifying_contract,
-    );
+    let domain = order_domain(chain_id, verifying_contract);
 
     let signature = signer
         .sign_typed_data_sync(&order, &domain)
@@ -161,13 +201,56 @@ where
     Ok(base64::engine::general_purpose::URL_SAFE.encode(result.into_bytes()))
 }
 
+/// Check an L2 HMAC signature against the exact body text that was sent
+///
+/// The counterpart of [`build_hmac_signature`], for servers. The comparison
+/// is constant-time.
+pub fn verify_hmac_signature(
+    secret: &str,
+    timestamp: &str,
+    method: &str,
+    request_path: &str,
+    body: &str,
+    signature: &str,
+) -> bool {
+    let engine = base64::engine::general_purpose::URL_SAFE;
+    let (Ok(key), Ok(signature)) = (engine.decode(secret), engine.decode(signature)) else {
+        return false;
+    };
+    let Ok(mut mac) = Hmac::<Sha256>::new_from_slice(&key) else {
+        return false;
+    };
+
+    let message = format!(
+        "{}{}{}{}",
+        timestamp,
+        method.to_uppercase(),
+        request_path,
+        body
+    );
+    mac.update(message.as_bytes());
+    mac.verify_slice(&signature).is_ok()
+}
+
 /// Create L1 headers for authentication (using private key signature)
 ///
 /// Generates initial authentication envelope using elliptic curve cryptography
 /// for establishing trusted communication channels with the distributed ledger API.
 pub fn create_l1_headers(signer: &PrivateKeySigner, nonce: Option<U256>) -> Result<Headers> {
+    create_l1_headers_at(signer, nonce, get_current_unix_time_secs())
+}
+
+/// Create L1 headers stamped with `timestamp` (Unix seconds)
+///
+/// Pass the exchange's time from a [`ClockSync`](crate::clock_sync::ClockSync)
+/// when the local clock cannot be trusted.
+pub fn create_l1_headers_at(
+    signer: &PrivateKeySigner,
+    nonce: Option<U256>,
+    timestamp: u64,
+) -> Result<Headers> {
     // Capture temporal context for replay prevention at protocol boundary
-    let timestamp = get_current_unix_time_secs().to_string();
+    let timestamp = timestamp.to_string();
     let nonce = nonce.unwrap_or(U256::ZERO);
 
     // Generate EIP-712 compliant signature for cryptographic proof of authority
@@ -194,12 +277,33 @@ pub fn create_l2_headers<T>(
     req_path: &str,
     body: Option<&T>,
 ) -> Result<Headers>
+where
+    T: ?Sized + Serialize,
+{
+    create_l2_headers_at(
+        signer,
+        api_creds,
+        method,
+        req_path,
+        body,
+        get_current_unix_time_secs(),
+    )
+}
+
+/// Create L2 headers stamped with `timestamp` (Unix seconds)
+pub fn create_l2_headers_at<T>(
+    signer: &PrivateKeySigner,
+    api_creds: &ApiCredentials,
+    method: &str,
+    req_path: &str,
+    body: Option<&T>,
+    timestamp: u64,
+) -> Result<Headers>
 where
     T: ?Sized + Serialize,
 {
     // Extract identity from signing authority for header binding
     let address = encode_prefixed(signer.address().as_slice());
-    let timestamp = get_current_unix_time_secs();
 
     // Generate cryptographic authenticator using temporal and message context
     let hmac_signature =
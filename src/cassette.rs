@@ -0,0 +1,516 @@
+//! HTTP record/replay for offline, deterministic tests
+//!
+//! A [`Cassette`] in record mode sends requests to the exchange and writes
+//! each request/response pair to a JSON file. In replay mode it serves those
+//! responses back without touching the network, so tests run against real
+//! exchange payloads instead of hand-written fixtures.
+//!
+//! Values that change on every run are stored as `*`: the L2 signature,
+//! timestamp, nonce and credential headers, and any `timestamp`, `salt`,
+//! `signature`, `expiration` or `next_cursor` field in a query or JSON body.
+//! Requests are matched with those values masked, and identical requests are
+//! replayed in the order they were recorded.
+//!
+//! Recorded responses never keep secrets: API credentials returned by the
+//! `/auth/*` endpoints and cookie or authorization headers are masked before
+//! they are stored. A recording cassette keeps its interactions in memory
+//! and writes them out on [`Cassette::save`] or when it is dropped.
+
+use crate::errors::{PolyfillError, Result};
+use reqwest::{Client, Request, Response};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Placeholder stored for a value that is not matched on replay
+const MASK: &str = "*";
+
+/// L2 headers that differ on every request or carry secrets
+const VOLATILE_HEADERS: &[&str] = &[
+    "poly_signature",
+    "poly_timestamp",
+    "poly_nonce",
+    "poly_api_key",
+    "poly_passphrase",
+];
+
+/// Response headers that can carry session secrets
+const SENSITIVE_RESPONSE_HEADERS: &[&str] = &["set-cookie", "authorization", "proxy-authorization"];
+
+/// Credential fields in `/auth/*` response bodies
+const CREDENTIAL_FIELDS: &[&str] = &["apiKey", "apiKeys", "secret", "passphrase"];
+
+/// Stand-in for a recorded API secret, valid base64 so replayed credentials still sign
+const MASKED_SECRET: &str = "AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA=";
+
+/// Query parameters and JSON body fields that differ between runs
+const VOLATILE_FIELDS: &[&str] = &[
+    "timestamp",
+    "salt",
+    "signature",
+    "expiration",
+    "next_cursor",
+];
+
+/// Whether a cassette talks to the exchange or only to its file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CassetteMode {
+    /// Send requests live and record every exchange
+    Record,
+    /// Serve recorded responses; unrecorded requests fail
+    Replay,
+}
+
+/// A request as stored in a cassette, with volatile values masked
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedRequest {
+    pub method: String,
+    pub path: String,
+    /// Query parameters, sorted by name
+    #[serde(default)]
+    pub query: Vec<(String, String)>,
+    /// `POLY_*` authentication headers
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+    #[serde(default)]
+    pub body: Option<Value>,
+}
+
+impl RecordedRequest {
+    /// Capture a fully built request
+    pub fn from_request(request: &Request) -> Self {
+        let mut query: Vec<(String, String)> = request
+            .url()
+            .query_pairs()
+            .map(|(name, value)| {
+                let value = if VOLATILE_FIELDS.contains(&name.as_ref()) {
+                    MASK.to_string()
+                } else {
+                    value.into_owned()
+                };
+                (name.into_owned(), value)
+            })
+            .collect();
+        query.sort();
+
+        let headers = request
+            .headers()
+            .iter()
+            .filter(|(name, _)| name.as_str().starts_with("poly_"))
+            .map(|(name, value)| {
+                let value = if VOLATILE_HEADERS.contains(&name.as_str()) {
+                    MASK.to_string()
+                } else {
+                    String::from_utf8_lossy(value.as_bytes()).into_owned()
+                };
+                (name.as_str().to_string(), value)
+            })
+            .collect();
+
+        let body = request
+            .body()
+            .and_then(|body| body.as_bytes())
+            .map(|bytes| {
+                serde_json::from_slice(bytes)
+                    .unwrap_or_else(|_| Value::String(String::from_utf8_lossy(bytes).into_owned()))
+            })
+            .map(|body| mask_fields(body, VOLATILE_FIELDS));
+
+        Self {
+            method: request.method().to_string(),
+            path: request.url().path().to_string(),
+            query,
+            headers,
+            body,
+        }
+    }
+}
+
+/// A response as stored in a cassette
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedResponse {
+    pub status: u16,
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+    pub body: String,
+}
+
+impl RecordedResponse {
+    /// Read a live response in full
+    async fn capture(response: Response) -> Result<Self> {
+        let status = response.status().as_u16();
+        let headers = response
+            .headers()
+            .iter()
+            .filter_map(|(name, value)| {
+                Some((name.as_str().to_string(), value.to_str().ok()?.to_string()))
+            })
+            .collect();
+        let body = String::from_utf8_lossy(&response.bytes().await?).into_owned();
+
+        Ok(Self {
+            status,
+            headers,
+            body,
+        })
+    }
+
+    /// Copy safe to store, with secrets in headers and `/auth/*` bodies masked
+    fn masked(&self, path: &str) -> Self {
+        let body = match serde_json::from_str(&self.body) {
+            Ok(body) if path.starts_with("/auth/") => {
+                mask_fields(body, CREDENTIAL_FIELDS).to_string()
+            },
+            _ => self.body.clone(),
+        };
+
+        // A rewritten body no longer matches the recorded length
+        let headers = self
+            .headers
+            .iter()
+            .filter(|(name, _)| body == self.body || name.as_str() != "content-length")
+            .map(|(name, value)| {
+                let value = if SENSITIVE_RESPONSE_HEADERS.contains(&name.as_str()) {
+                    MASK.to_string()
+                } else {
+                    value.clone()
+                };
+                (name.clone(), value)
+            })
+            .collect();
+
+        Self {
+            status: self.status,
+            headers,
+            body,
+        }
+    }
+
+    /// Rebuild a response the client can read as if it came off the wire
+    fn to_response(&self) -> Result<Response> {
+        let mut builder = http::Response::builder().status(self.status);
+        for (name, value) in &self.headers {
+            builder = builder.header(name, value);
+        }
+        let response = builder
+            .body(self.body.clone())
+            .map_err(|e| PolyfillError::internal("Invalid recorded response", e))?;
+        Ok(Response::from(response))
+    }
+}
+
+/// One request and the response the exchange gave it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Interaction {
+    pub request: RecordedRequest,
+    pub response: RecordedResponse,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CassetteFile {
+    interactions: Vec<Interaction>,
+}
+
+#[derive(Debug, Default)]
+struct CassetteState {
+    interactions: Vec<Interaction>,
+    /// Replay mode: whether each interaction has been served
+    served: Vec<bool>,
+    /// Record mode: whether interactions were added since the last save
+    unsaved: bool,
+}
+
+/// Recorded HTTP exchanges backed by a JSON file
+#[derive(Debug)]
+pub struct Cassette {
+    path: PathBuf,
+    mode: CassetteMode,
+    state: Mutex<CassetteState>,
+}
+
+impl Cassette {
+    /// Record into `path`, replacing anything recorded there before
+    ///
+    /// The file is written by [`save`](Self::save) or when the cassette is
+    /// dropped.
+    pub fn record(path: impl AsRef<Path>) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+            mode: CassetteMode::Record,
+            state: Mutex::new(CassetteState::default()),
+        }
+    }
+
+    /// Replay the interactions recorded in `path`
+    pub fn replay(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let contents = std::fs::read_to_string(&path).map_err(|e| {
+            PolyfillError::config(format!("Failed to read cassette {}: {}", path.display(), e))
+        })?;
+        let file: CassetteFile = serde_json::from_str(&contents)?;
+
+        Ok(Self {
+            path,
+            mode: CassetteMode::Replay,
+            state: Mutex::new(CassetteState {
+                served: vec![false; file.interactions.len()],
+                interactions: file.interactions,
+                unsaved: false,
+            }),
+        })
+    }
+
+    /// Record into `path` if `record` is set, otherwise replay from it
+    ///
+    /// Lets a test re-record its cassette by flipping one flag or env var.
+    pub fn open(path: impl AsRef<Path>, record: bool) -> Result<Self> {
+        if record {
+            Ok(Self::record(path))
+        } else {
+            Self::replay(path)
+        }
+    }
+
+    pub fn mode(&self) -> CassetteMode {
+        self.mode
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Every interaction recorded or loaded so far
+    pub fn interactions(&self) -> Vec<Interaction> {
+        self.state.lock().unwrap().interactions.clone()
+    }
+
+    /// Number of loaded interactions not yet replayed
+    pub fn remaining(&self) -> usize {
+        let state = self.state.lock().unwrap();
+        state.served.iter().filter(|served| !**served).count()
+    }
+
+    /// Write every interaction to the cassette file
+    pub fn save(&self) -> Result<()> {
+        let file = CassetteFile {
+            interactions: self.interactions(),
+        };
+        let contents = serde_json::to_string_pretty(&file)?;
+        std::fs::write(&self.path, contents).map_err(|e| {
+            PolyfillError::config(format!(
+                "Failed to write cassette {}: {}",
+                self.path.display(),
+                e
+            ))
+        })?;
+        self.state.lock().unwrap().unsaved = false;
+        Ok(())
+    }
+
+    /// Send `request` through the cassette
+    ///
+    /// Record mode sends it with `http_client` and keeps the exchange; replay
+    /// mode serves the first unplayed matching response.
+    pub async fn execute(&self, http_client: &Client, request: Request) -> Result<Response> {
+        let recorded = RecordedRequest::from_request(&request);
+
+        match self.mode {
+            CassetteMode::Replay => self.replay_response(&recorded)?.to_response(),
+            CassetteMode::Record => {
+                let response =
+                    RecordedResponse::capture(http_client.execute(request).await?).await?;
+                let live = response.to_response()?;
+                let response = response.masked(&recorded.path);
+
+                let mut state = self.state.lock().unwrap();
+                state.interactions.push(Interaction {
+                    request: recorded,
+                    response,
+                });
+                state.served.push(true);
+                state.unsaved = true;
+                Ok(live)
+            },
+        }
+    }
+
+    fn replay_response(&self, request: &RecordedRequest) -> Result<RecordedResponse> {
+        let mut state = self.state.lock().unwrap();
+        let CassetteState {
+            interactions,
+            served,
+            ..
+        } = &mut *state;
+
+        let index = interactions
+            .iter()
+            .zip(served.iter())
+            .position(|(interaction, served)| !served && interaction.request == *request)
+            .ok_or_else(|| {
+                PolyfillError::config(format!(
+                    "No recorded response left for {} {} in {}",
+                    request.method,
+                    request.path,
+                    self.path.display()
+                ))
+            })?;
+
+        served[index] = true;
+        Ok(interactions[index].response.clone())
+    }
+}
+
+impl Drop for Cassette {
+    fn drop(&mut self) {
+        if !self.state.get_mut().is_ok_and(|state| state.unsaved) {
+            return;
+        }
+        if let Err(e) = self.save() {
+            tracing::warn!("Failed to save cassette on drop: {}", e);
+        }
+    }
+}
+
+/// Mask the named fields anywhere in a JSON value
+fn mask_fields(value: Value, names: &[&str]) -> Value {
+    match value {
+        Value::Object(fields) => Value::Object(
+            fields
+                .into_iter()
+                .map(|(name, value)| {
+                    let value = if names.contains(&name.as_str()) {
+                        mask_value(&name, value)
+                    } else {
+                        mask_fields(value, names)
+                    };
+                    (name, value)
+                })
+                .collect(),
+        ),
+        Value::Array(values) => Value::Array(
+            values
+                .into_iter()
+                .map(|value| mask_fields(value, names))
+                .collect(),
+        ),
+        other => other,
+    }
+}
+
+/// Replace every scalar in a masked field, keeping arrays and objects intact
+fn mask_value(name: &str, value: Value) -> Value {
+    match value {
+        Value::Array(values) => Value::Array(
+            values
+                .into_iter()
+                .map(|value| mask_value(name, value))
+                .collect(),
+        ),
+        Value::Object(fields) => Value::Object(
+            fields
+                .into_iter()
+                .map(|(field, value)| (field, mask_value(name, value)))
+                .collect(),
+        ),
+        _ if name == "secret" => Value::String(MASKED_SECRET.to_string()),
+        _ => Value::String(MASK.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recorded_request_masks_volatile_values() {
+        let build = |signature: &str, cursor: &str, salt: u64| {
+            let request = Client::new()
+                .post(format!(
+                    "https://clob.example.com/order?next_cursor={}",
+                    cursor
+                ))
+                .header("POLY_ADDRESS", "0x1")
+                .header("POLY_SIGNATURE", signature)
+                .header("POLY_TIMESTAMP", signature.len().to_string())
+                .json(&serde_json::json!({"order": {"salt": salt, "tokenId": "123"}}))
+                .build()
+                .unwrap();
+            RecordedRequest::from_request(&request)
+        };
+
+        let first = build("sig-a", "MA==", 1);
+        assert_eq!(first, build("sig-bb", "MQ==", 2));
+        assert_eq!(first.headers["poly_address"], "0x1");
+        assert_eq!(first.headers["poly_signature"], MASK);
+        assert_eq!(
+            first.body,
+            Some(serde_json::json!({"order": {"salt": "*", "tokenId": "123"}}))
+        );
+        assert_eq!(
+            first.query,
+            vec![("next_cursor".to_string(), MASK.to_string())]
+        );
+    }
+
+    #[test]
+    fn test_recorded_response_masks_credentials() {
+        let response = RecordedResponse {
+            status: 200,
+            headers: BTreeMap::from([
+                ("content-type".to_string(), "application/json".to_string()),
+                ("set-cookie".to_string(), "session=abc".to_string()),
+            ]),
+            body: r#"{"apiKey": "key", "secret": "c2VjcmV0", "passphrase": "pass"}"#.to_string(),
+        };
+
+        let masked = response.masked("/auth/derive-api-key");
+        assert_eq!(masked.headers["content-type"], "application/json");
+        assert_eq!(masked.headers["set-cookie"], MASK);
+        let credentials: crate::types::ApiCredentials = serde_json::from_str(&masked.body).unwrap();
+        assert_eq!(credentials.api_key, MASK);
+        assert_eq!(credentials.secret, MASKED_SECRET);
+        assert_eq!(credentials.passphrase, MASK);
+
+        // Only auth endpoints carry credentials; other bodies are kept verbatim
+        let book = RecordedResponse {
+            body: r#"{"secret": "kept"}"#.to_string(),
+            ..response
+        };
+        assert_eq!(book.masked("/book").body, book.body);
+    }
+
+    #[test]
+    fn test_recording_is_saved_on_drop() {
+        let path =
+            std::env::temp_dir().join(format!("polyfill-cassette-{}.json", uuid::Uuid::new_v4()));
+        let cassette = Cassette::record(&path);
+        cassette
+            .state
+            .lock()
+            .unwrap()
+            .interactions
+            .push(Interaction {
+                request: RecordedRequest {
+                    method: "GET".to_string(),
+                    path: "/time".to_string(),
+                    query: Vec::new(),
+                    headers: BTreeMap::new(),
+                    body: None,
+                },
+                response: RecordedResponse {
+                    status: 200,
+                    headers: BTreeMap::new(),
+                    body: "1700000000".to_string(),
+                },
+            });
+        cassette.state.lock().unwrap().unsaved = true;
+        assert!(!path.exists());
+
+        drop(cassette);
+        let replay = Cassette::replay(&path).unwrap();
+        assert_eq!(replay.remaining(), 1);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,339 @@
+//! Exchange clock synchronisation
+//!
+//! L1 and L2 headers carry a timestamp the exchange checks against its own
+//! clock, and GTD expirations are judged by it too. On a host whose clock has
+//! drifted, signed requests fail with little explanation. [`ClockSync`]
+//! samples the exchange's `/time` endpoint, estimates the offset between the
+//! two clocks and the round-trip time, and gives the client corrected
+//! timestamps for every header and order it signs.
+//!
+//! `/time` reports whole seconds, so a single sample only bounds the offset
+//! to a window about a second wide. Samples are intersected to narrow it down;
+//! if they stop agreeing (the local clock was stepped), the estimate restarts
+//! from the latest sample.
+//!
+//! Once synced, timestamps are corrected, so an auth failure is only blamed
+//! on the clock when the exchange says it rejected the timestamp. A client
+//! that has never synced samples `/time` once on its first auth failure, so
+//! an uncorrected clock is both diagnosed and corrected for the next request.
+
+use crate::client::ClobClient;
+use crate::errors::{AuthErrorKind, PolyfillError, Result};
+use crate::utils::time::now_millis;
+use chrono::{DateTime, Utc};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, AtomicI64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tracing::warn;
+
+/// Drift beyond which signed requests are likely to be rejected
+pub const DEFAULT_MAX_DRIFT: Duration = Duration::from_secs(5);
+
+/// Samples kept for the offset estimate
+const MAX_SAMPLES: usize = 16;
+
+/// One reading of the exchange clock
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClockSample {
+    /// Local Unix millis when the request was sent
+    pub sent_millis: u64,
+    /// Exchange time in Unix seconds
+    pub server_secs: u64,
+    /// Local Unix millis when the response arrived
+    pub received_millis: u64,
+}
+
+impl ClockSample {
+    pub fn rtt(&self) -> Duration {
+        Duration::from_millis(self.received_millis.saturating_sub(self.sent_millis))
+    }
+
+    /// Range of exchange-minus-local offsets, in millis, consistent with the sample
+    ///
+    /// The exchange read its clock somewhere between sending and receiving,
+    /// and at any point within the second it reported.
+    fn offset_bounds(&self) -> (i64, i64) {
+        let server_millis = (self.server_secs * 1000) as i64;
+        (
+            server_millis - self.received_millis as i64,
+            server_millis + 999 - self.sent_millis as i64,
+        )
+    }
+}
+
+#[derive(Debug, Default)]
+struct Estimate {
+    samples: VecDeque<ClockSample>,
+    /// Intersection of the offset bounds of `samples`
+    bounds: Option<(i64, i64)>,
+}
+
+/// Estimated offset between the local and exchange clocks
+#[derive(Debug)]
+pub struct ClockSync {
+    max_drift: Duration,
+    /// Exchange minus local time, in millis
+    offset_ms: AtomicI64,
+    synced: AtomicBool,
+    estimate: Mutex<Estimate>,
+}
+
+impl Default for ClockSync {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ClockSync {
+    /// Unsynchronised clock that reports local time until the first sample
+    pub fn new() -> Self {
+        Self::with_max_drift(DEFAULT_MAX_DRIFT)
+    }
+
+    /// Treat offsets larger than `max_drift` as clock skew
+    pub fn with_max_drift(max_drift: Duration) -> Self {
+        Self {
+            max_drift,
+            offset_ms: AtomicI64::new(0),
+            synced: AtomicBool::new(false),
+            estimate: Mutex::new(Estimate::default()),
+        }
+    }
+
+    pub fn max_drift(&self) -> Duration {
+        self.max_drift
+    }
+
+    /// Fold a sample into the estimate
+    pub fn record(&self, sample: ClockSample) {
+        let mut estimate = self.estimate.lock().unwrap();
+        let (lo, hi) = sample.offset_bounds();
+
+        let bounds = match estimate.bounds {
+            Some((cur_lo, cur_hi)) if lo.max(cur_lo) <= hi.min(cur_hi) => {
+                (lo.max(cur_lo), hi.min(cur_hi))
+            },
+            Some(_) => {
+                // The clocks moved relative to each other; start over
+                estimate.samples.clear();
+                (lo, hi)
+            },
+            None => (lo, hi),
+        };
+
+        if estimate.samples.len() == MAX_SAMPLES {
+            estimate.samples.pop_front();
+        }
+        estimate.samples.push_back(sample);
+        estimate.bounds = Some(bounds);
+
+        self.offset_ms
+            .store(bounds.0 + (bounds.1 - bounds.0) / 2, Ordering::Relaxed);
+        self.synced.store(true, Ordering::Relaxed);
+    }
+
+    /// Whether at least one sample has been recorded
+    pub fn is_synced(&self) -> bool {
+        self.synced.load(Ordering::Relaxed)
+    }
+
+    /// Exchange time minus local time, in millis (0 until synced)
+    pub fn offset_ms(&self) -> i64 {
+        self.offset_ms.load(Ordering::Relaxed)
+    }
+
+    /// Width of the window the true offset lies in
+    pub fn uncertainty(&self) -> Option<Duration> {
+        let estimate = self.estimate.lock().unwrap();
+        let (lo, hi) = estimate.bounds?;
+        Some(Duration::from_millis((hi - lo) as u64))
+    }
+
+    /// Smallest round-trip time among the samples in use
+    pub fn rtt(&self) -> Option<Duration> {
+        let estimate = self.estimate.lock().unwrap();
+        estimate.samples.iter().map(ClockSample::rtt).min()
+    }
+
+    pub fn last_sample(&self) -> Option<ClockSample> {
+        self.estimate.lock().unwrap().samples.back().copied()
+    }
+
+    /// Exchange time in Unix millis
+    pub fn now_millis(&self) -> u64 {
+        (now_millis() as i64 + self.offset_ms()).max(0) as u64
+    }
+
+    /// Exchange time in Unix seconds, as signed into headers
+    pub fn now_secs(&self) -> u64 {
+        self.now_millis() / 1000
+    }
+
+    /// Exchange time
+    pub fn now(&self) -> DateTime<Utc> {
+        DateTime::from_timestamp_millis(self.now_millis() as i64).unwrap_or_else(Utc::now)
+    }
+
+    /// Whether the local clock is further than `max_drift` from the exchange's
+    pub fn drift_exceeded(&self) -> bool {
+        self.offset_ms().unsigned_abs() > self.max_drift.as_millis() as u64
+    }
+
+    /// Fail with `AuthErrorKind::ClockSkew` if the drift is too large
+    ///
+    /// Timestamps are corrected either way; this is for callers that would
+    /// rather fix the host clock than rely on the correction.
+    pub fn check(&self) -> Result<()> {
+        if self.drift_exceeded() {
+            return Err(self.skew_error("Local clock is out of sync with the exchange"));
+        }
+        Ok(())
+    }
+
+    /// Blame an auth failure on clock skew where the timestamp is the likely cause
+    ///
+    /// A rejection the exchange already attributes to the timestamp gains
+    /// the measured drift. Any other auth failure is relabelled only if the
+    /// request was `stamped_unsynced`, i.e. signed before the offset was
+    /// known, and the clocks have since been measured to be too far apart.
+    pub fn diagnose(&self, error: PolyfillError, stamped_unsynced: bool) -> PolyfillError {
+        match error {
+            PolyfillError::Auth { message, kind }
+                if (kind == AuthErrorKind::ClockSkew || stamped_unsynced)
+                    && self.is_synced()
+                    && self.drift_exceeded() =>
+            {
+                self.skew_error(message)
+            },
+            error => error,
+        }
+    }
+
+    fn skew_error(&self, message: impl std::fmt::Display) -> PolyfillError {
+        let offset = self.offset_ms();
+        PolyfillError::Auth {
+            message: format!(
+                "{} (local clock is {:.1}s {} the exchange, more than the {:?} allowed)",
+                message,
+                offset.unsigned_abs() as f64 / 1000.0,
+                if offset > 0 { "behind" } else { "ahead of" },
+                self.max_drift
+            ),
+            kind: AuthErrorKind::ClockSkew,
+        }
+    }
+
+    /// Sample the exchange clock once through `client`
+    pub async fn sync(&self, client: &ClobClient) -> Result<ClockSample> {
+        let sent_millis = now_millis();
+        let server_secs = client.get_server_time().await?;
+        let sample = ClockSample {
+            sent_millis,
+            server_secs,
+            received_millis: now_millis(),
+        };
+
+        self.record(sample);
+        if self.drift_exceeded() {
+            warn!(
+                "Local clock is {}ms off the exchange; correcting signed timestamps",
+                self.offset_ms()
+            );
+        }
+        Ok(sample)
+    }
+
+    /// Sample through `client` every `interval` until the task is aborted
+    pub fn spawn_sync_task(
+        self: &Arc<Self>,
+        client: Arc<ClobClient>,
+        interval: Duration,
+    ) -> JoinHandle<()> {
+        let clock = Arc::clone(self);
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                if let Err(e) = clock.sync(&client).await {
+                    warn!("Failed to sample exchange time: {}", e);
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_samples_narrow_the_offset() {
+        let clock = ClockSync::new();
+        assert!(!clock.is_synced());
+        assert_eq!(clock.offset_ms(), 0);
+
+        // Exchange is 30.25s ahead; each reading truncates to whole seconds
+        let true_offset = 30_250;
+        for sent in [1_700_000_000_000u64, 1_700_000_060_400, 1_700_000_120_800] {
+            let received = sent + 40;
+            let server_secs = (sent + 20 + true_offset) / 1000;
+            clock.record(ClockSample {
+                sent_millis: sent,
+                server_secs,
+                received_millis: received,
+            });
+        }
+
+        assert!(clock.uncertainty().unwrap() < Duration::from_millis(1000));
+        assert!((clock.offset_ms() - true_offset as i64).abs() < 500);
+        assert_eq!(clock.rtt(), Some(Duration::from_millis(40)));
+        assert!(clock.drift_exceeded());
+
+        match clock.check() {
+            Err(PolyfillError::Auth { kind, message }) => {
+                assert_eq!(kind, AuthErrorKind::ClockSkew);
+                assert!(message.contains("behind"));
+            },
+            other => panic!("expected clock skew, got {:?}", other),
+        }
+        let unauthorized = || PolyfillError::auth("POST /order: Unauthorized");
+        assert!(matches!(
+            clock.diagnose(unauthorized(), true),
+            PolyfillError::Auth {
+                kind: AuthErrorKind::ClockSkew,
+                ..
+            }
+        ));
+        // Timestamps signed after syncing were corrected; the clock is not to blame
+        assert!(matches!(
+            clock.diagnose(unauthorized(), false),
+            PolyfillError::Auth {
+                kind: AuthErrorKind::SignatureError,
+                ..
+            }
+        ));
+        let rejected = PolyfillError::Auth {
+            message: "POST /order: invalid timestamp".to_string(),
+            kind: AuthErrorKind::ClockSkew,
+        };
+        match clock.diagnose(rejected, false) {
+            PolyfillError::Auth { kind, message } => {
+                assert_eq!(kind, AuthErrorKind::ClockSkew);
+                assert!(message.contains("behind"));
+            },
+            other => panic!("expected clock skew, got {:?}", other),
+        }
+
+        // A stepped clock no longer fits the window; the estimate restarts
+        let sent = 1_700_000_200_000;
+        clock.record(ClockSample {
+            sent_millis: sent,
+            server_secs: sent / 1000,
+            received_millis: sent + 10,
+        });
+        assert!(clock.offset_ms().abs() < 1000);
+        assert!(clock.check().is_ok());
+    }
+}
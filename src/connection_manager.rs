@@ -4,7 +4,7 @@
 //! connection drops that cause 200ms+ reconnection overhead.
 
 use reqwest::Client;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::Mutex;
@@ -16,6 +16,18 @@ pub struct ConnectionManager {
     base_url: String,
     running: Arc<AtomicBool>,
     handle: Arc<Mutex<Option<JoinHandle<()>>>>,
+    pings: Arc<PingCounters>,
+    /// Millis between keep-alive pings while the task is running
+    interval_millis: AtomicU64,
+}
+
+/// Keep-alive ping outcomes, shared with the background task
+#[derive(Debug, Default)]
+struct PingCounters {
+    sent: AtomicU64,
+    failed: AtomicU64,
+    /// Unix millis of the last ping the server answered, 0 if none yet
+    last_success: AtomicU64,
 }
 
 impl ConnectionManager {
@@ -26,6 +38,8 @@ impl ConnectionManager {
             base_url,
             running: Arc::new(AtomicBool::new(false)),
             handle: Arc::new(Mutex::new(None)),
+            pings: Arc::new(PingCounters::default()),
+            interval_millis: AtomicU64::new(0),
         }
     }
 
@@ -38,20 +52,24 @@ impl ConnectionManager {
         }
 
         self.running.store(true, Ordering::Relaxed);
+        self.interval_millis
+            .store(interval.as_millis() as u64, Ordering::Relaxed);
 
         let client = self.client.clone();
         let base_url = self.base_url.clone();
         let running = self.running.clone();
+        let pings = self.pings.clone();
 
         let handle = tokio::spawn(async move {
             while running.load(Ordering::Relaxed) {
                 // Send a lightweight request to keep connection alive
                 // Use /time endpoint as it's fast and doesn't require auth
-                let _ = client
+                let result = client
                     .get(format!("{}/time", base_url))
                     .timeout(Duration::from_secs(5))
                     .send()
                     .await;
+                let _ = record_ping(&pings, result);
 
                 // Wait for next interval
                 tokio::time::sleep(interval).await;
@@ -77,15 +95,55 @@ impl ConnectionManager {
         self.running.load(Ordering::Relaxed)
     }
 
+    /// Time between keep-alive pings, if the task is running
+    pub fn keepalive_interval(&self) -> Option<Duration> {
+        self.is_running()
+            .then(|| Duration::from_millis(self.interval_millis.load(Ordering::Relaxed)))
+    }
+
     /// Send a single keep-alive ping
     pub async fn ping(&self) -> Result<(), reqwest::Error> {
-        self.client
+        let result = self
+            .client
             .get(format!("{}/time", self.base_url))
             .timeout(Duration::from_secs(5))
             .send()
-            .await?;
-        Ok(())
+            .await;
+        record_ping(&self.pings, result)
+    }
+
+    /// Unix millis of the last ping the server answered successfully
+    pub fn last_success_millis(&self) -> Option<u64> {
+        match self.pings.last_success.load(Ordering::Relaxed) {
+            0 => None,
+            millis => Some(millis),
+        }
+    }
+
+    /// Pings sent and failed so far, for [`crate::metrics`]
+    pub fn keepalive_metrics(&self) -> crate::metrics::KeepaliveMetrics {
+        crate::metrics::KeepaliveMetrics {
+            pings: self.pings.sent.load(Ordering::Relaxed),
+            failures: self.pings.failed.load(Ordering::Relaxed),
+            last_success_millis: self.last_success_millis(),
+        }
+    }
+}
+
+/// Count a ping and remember when the server last answered one with a success status
+fn record_ping(
+    pings: &PingCounters,
+    result: Result<reqwest::Response, reqwest::Error>,
+) -> Result<(), reqwest::Error> {
+    pings.sent.fetch_add(1, Ordering::Relaxed);
+    if let Err(e) = result.and_then(|response| response.error_for_status()) {
+        pings.failed.fetch_add(1, Ordering::Relaxed);
+        return Err(e);
     }
+    pings
+        .last_success
+        .store(crate::utils::time::now_millis(), Ordering::Relaxed);
+    Ok(())
 }
 
 impl Drop for ConnectionManager {
@@ -0,0 +1,524 @@
+//! Data API: positions, activity, portfolio value and top holders
+//!
+//! Polymarket's data API reports what a wallet holds and has done on chain,
+//! independently of the CLOB's order and trade records. Positions and
+//! activity are keyed by the proxy wallet address, which is the `funder` of
+//! a [`ClobClient`] trading through a proxy, and carry the CLOB
+//! `condition_id` and token ID (`asset`) they refer to.
+//!
+//! Like [`GammaClient`](crate::gamma::GammaClient), [`DataApiClient`] sends
+//! its requests through a [`ClobClient`] sharing the CLOB client's transport,
+//! and exposes offset-based lists as [`Paginator`]s.
+
+use crate::client::ClobClient;
+use crate::errors::Result;
+use crate::pagination::Paginator;
+use crate::types::Side;
+use futures::TryStreamExt;
+use rust_decimal::Decimal;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Deserializer};
+
+/// Production data API host
+pub const DATA_API_HOST: &str = "https://data-api.polymarket.com";
+
+/// Page size used when the query does not set a limit
+pub const DEFAULT_PAGE_SIZE: u32 = 100;
+
+/// A wallet's holding of one outcome token
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Position {
+    pub proxy_wallet: String,
+    /// CLOB token ID
+    pub asset: String,
+    pub condition_id: String,
+    pub size: Decimal,
+    pub avg_price: Decimal,
+    #[serde(default)]
+    pub initial_value: Decimal,
+    pub current_value: Decimal,
+    #[serde(default)]
+    pub cash_pnl: Decimal,
+    #[serde(default)]
+    pub percent_pnl: Decimal,
+    #[serde(default)]
+    pub realized_pnl: Decimal,
+    #[serde(default)]
+    pub total_bought: Decimal,
+    #[serde(default)]
+    pub cur_price: Decimal,
+    /// The market has resolved and the position can be redeemed
+    #[serde(default)]
+    pub redeemable: bool,
+    /// The wallet holds both outcomes and can merge them back into collateral
+    #[serde(default)]
+    pub mergeable: bool,
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub slug: Option<String>,
+    #[serde(default)]
+    pub event_slug: Option<String>,
+    #[serde(default)]
+    pub outcome: Option<String>,
+    #[serde(default)]
+    pub outcome_index: Option<u32>,
+    /// Token ID of the complementary outcome
+    #[serde(default)]
+    pub opposite_asset: Option<String>,
+    #[serde(default)]
+    pub end_date: Option<String>,
+    #[serde(default)]
+    pub negative_risk: bool,
+}
+
+/// Kind of on-chain activity
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum ActivityType {
+    Trade,
+    Split,
+    Merge,
+    Redeem,
+    Reward,
+    Conversion,
+    /// A kind added to the API after this client was written
+    #[serde(other)]
+    Unknown,
+}
+
+impl ActivityType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ActivityType::Trade => "TRADE",
+            ActivityType::Split => "SPLIT",
+            ActivityType::Merge => "MERGE",
+            ActivityType::Redeem => "REDEEM",
+            ActivityType::Reward => "REWARD",
+            ActivityType::Conversion => "CONVERSION",
+            ActivityType::Unknown => "UNKNOWN",
+        }
+    }
+}
+
+/// One entry of a wallet's activity feed
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Activity {
+    pub proxy_wallet: String,
+    /// Unix seconds
+    pub timestamp: u64,
+    #[serde(default)]
+    pub condition_id: Option<String>,
+    #[serde(rename = "type")]
+    pub activity_type: ActivityType,
+    /// Size in outcome tokens
+    #[serde(default)]
+    pub size: Decimal,
+    /// Size in collateral
+    #[serde(default)]
+    pub usdc_size: Decimal,
+    #[serde(default)]
+    pub price: Option<Decimal>,
+    /// CLOB token ID, for trades
+    #[serde(default, deserialize_with = "empty_as_none")]
+    pub asset: Option<String>,
+    /// Side of a trade, from the wallet's point of view
+    #[serde(default, deserialize_with = "empty_as_none")]
+    pub side: Option<Side>,
+    #[serde(default)]
+    pub outcome: Option<String>,
+    #[serde(default)]
+    pub transaction_hash: Option<String>,
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub slug: Option<String>,
+}
+
+/// Value of a wallet's open positions, in collateral
+#[derive(Debug, Clone, Deserialize)]
+pub struct PortfolioValue {
+    pub user: String,
+    pub value: Decimal,
+}
+
+/// A wallet among the largest holders of one outcome
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Holder {
+    pub proxy_wallet: String,
+    pub amount: Decimal,
+    #[serde(default)]
+    pub outcome_index: Option<u32>,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub pseudonym: Option<String>,
+}
+
+/// Largest holders of one outcome token
+#[derive(Debug, Clone, Deserialize)]
+pub struct TokenHolders {
+    /// CLOB token ID
+    pub token: String,
+    #[serde(default)]
+    pub holders: Vec<Holder>,
+}
+
+/// Filters for listing positions
+#[derive(Debug, Clone, Default)]
+pub struct PositionParams {
+    /// Condition IDs to restrict to
+    pub markets: Vec<String>,
+    /// Skip positions smaller than this many tokens
+    pub size_threshold: Option<Decimal>,
+    pub redeemable: Option<bool>,
+    pub mergeable: Option<bool>,
+    /// Field to sort by, e.g. `CURRENT` or `CASHPNL`
+    pub sort_by: Option<String>,
+    /// `ASC` or `DESC`
+    pub sort_direction: Option<String>,
+    /// Page size, [`DEFAULT_PAGE_SIZE`] if unset
+    pub limit: Option<u32>,
+}
+
+impl PositionParams {
+    pub fn to_query_params(&self) -> Vec<(&'static str, String)> {
+        let mut params = Vec::new();
+
+        if !self.markets.is_empty() {
+            params.push(("market", self.markets.join(",")));
+        }
+        if let Some(x) = self.size_threshold {
+            params.push(("sizeThreshold", x.to_string()));
+        }
+        if let Some(x) = self.redeemable {
+            params.push(("redeemable", x.to_string()));
+        }
+        if let Some(x) = self.mergeable {
+            params.push(("mergeable", x.to_string()));
+        }
+        if let Some(x) = &self.sort_by {
+            params.push(("sortBy", x.clone()));
+        }
+        if let Some(x) = &self.sort_direction {
+            params.push(("sortDirection", x.clone()));
+        }
+        params
+    }
+}
+
+/// Filters for listing activity
+#[derive(Debug, Clone, Default)]
+pub struct ActivityParams {
+    /// Condition IDs to restrict to
+    pub markets: Vec<String>,
+    pub types: Vec<ActivityType>,
+    pub side: Option<Side>,
+    /// Unix seconds
+    pub start: Option<u64>,
+    /// Unix seconds
+    pub end: Option<u64>,
+    /// Page size, [`DEFAULT_PAGE_SIZE`] if unset
+    pub limit: Option<u32>,
+}
+
+impl ActivityParams {
+    pub fn to_query_params(&self) -> Vec<(&'static str, String)> {
+        let mut params = Vec::new();
+
+        if !self.markets.is_empty() {
+            params.push(("market", self.markets.join(",")));
+        }
+        if !self.types.is_empty() {
+            let types: Vec<&str> = self.types.iter().map(ActivityType::as_str).collect();
+            params.push(("type", types.join(",")));
+        }
+        if let Some(x) = self.side {
+            params.push(("side", x.as_str().to_string()));
+        }
+        if let Some(x) = self.start {
+            params.push(("start", x.to_string()));
+        }
+        if let Some(x) = self.end {
+            params.push(("end", x.to_string()));
+        }
+        params
+    }
+}
+
+/// Client for the data API
+pub struct DataApiClient {
+    client: ClobClient,
+}
+
+impl DataApiClient {
+    /// Client for the production data API, sharing `clob`'s transport
+    pub fn new(clob: &ClobClient) -> Result<Self> {
+        Self::with_host(clob, DATA_API_HOST)
+    }
+
+    /// Client for a data-API-compatible `host`, sharing `clob`'s transport
+    ///
+    /// As with Gamma, the CLOB rate limiter is not shared.
+    pub fn with_host(clob: &ClobClient, host: &str) -> Result<Self> {
+        let client = ClobClient::builder(host)
+            .chain_id(clob.chain_id())
+            .shared_with(clob)
+            .rate_limiter(None)
+            .build()?;
+        Ok(Self { client })
+    }
+
+    /// Client the requests are sent through, e.g. for its metrics
+    pub fn transport(&self) -> &ClobClient {
+        &self.client
+    }
+
+    /// Build a paginator over an offset-based list endpoint for `user`
+    fn paginate<T: DeserializeOwned + Send + 'static>(
+        &self,
+        path: &'static str,
+        user: &str,
+        mut query_params: Vec<(&'static str, String)>,
+        limit: Option<u32>,
+        offset: Option<u32>,
+    ) -> Paginator<'_, T> {
+        query_params.push(("user", user.to_string()));
+        self.client.paginate_offsets(
+            path,
+            query_params,
+            offset.unwrap_or(0),
+            limit.unwrap_or(DEFAULT_PAGE_SIZE),
+        )
+    }
+
+    /// Stream the positions of `user` page by page, starting at `offset`
+    pub fn stream_positions(
+        &self,
+        user: &str,
+        params: Option<&PositionParams>,
+        offset: Option<u32>,
+    ) -> Paginator<'_, Position> {
+        let params = params.cloned().unwrap_or_default();
+        self.paginate(
+            "/positions",
+            user,
+            params.to_query_params(),
+            params.limit,
+            offset,
+        )
+    }
+
+    /// Every position of `user` matching `params`
+    pub async fn get_positions(
+        &self,
+        user: &str,
+        params: Option<&PositionParams>,
+    ) -> Result<Vec<Position>> {
+        self.stream_positions(user, params, None)
+            .try_collect()
+            .await
+    }
+
+    /// Stream the activity of `user`, newest first, starting at `offset`
+    pub fn stream_activity(
+        &self,
+        user: &str,
+        params: Option<&ActivityParams>,
+        offset: Option<u32>,
+    ) -> Paginator<'_, Activity> {
+        let params = params.cloned().unwrap_or_default();
+        self.paginate(
+            "/activity",
+            user,
+            params.to_query_params(),
+            params.limit,
+            offset,
+        )
+    }
+
+    /// Every activity entry of `user` matching `params`
+    pub async fn get_activity(
+        &self,
+        user: &str,
+        params: Option<&ActivityParams>,
+    ) -> Result<Vec<Activity>> {
+        self.stream_activity(user, params, None).try_collect().await
+    }
+
+    /// Value of the open positions of `user`, optionally within `markets`
+    pub async fn get_portfolio_value(&self, user: &str, markets: &[String]) -> Result<Decimal> {
+        let mut query = vec![("user", user.to_string())];
+        if !markets.is_empty() {
+            query.push(("market", markets.join(",")));
+        }
+
+        let values: Vec<PortfolioValue> = self.client.get_json("/value", &query).await?;
+        Ok(values.iter().map(|v| v.value).sum())
+    }
+
+    /// Largest holders of each outcome of the market `condition_id`
+    ///
+    /// `limit` caps the holders listed per outcome.
+    pub async fn get_top_holders(
+        &self,
+        condition_id: &str,
+        limit: Option<u32>,
+    ) -> Result<Vec<TokenHolders>> {
+        let mut query = vec![("market", condition_id.to_string())];
+        if let Some(limit) = limit {
+            query.push(("limit", limit.to_string()));
+        }
+        self.client.get_json("/holders", &query).await
+    }
+}
+
+/// The data API sends an empty string where a value does not apply
+fn empty_as_none<'de, D, T>(deserializer: D) -> std::result::Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: DeserializeOwned,
+{
+    use serde::de::Error;
+
+    match Option::<serde_json::Value>::deserialize(deserializer)? {
+        None | Some(serde_json::Value::Null) => Ok(None),
+        Some(serde_json::Value::String(s)) if s.is_empty() => Ok(None),
+        Some(value) => serde_json::from_value(value)
+            .map(Some)
+            .map_err(D::Error::custom),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::{Matcher, Server};
+    use rust_decimal_macros::dec;
+
+    const USER: &str = "0x56687bf447db6ffa42ffe2204a05edaa20f55839";
+
+    fn position_json(asset: u32, redeemable: bool) -> String {
+        format!(
+            r#"{{"proxyWallet": "{USER}", "asset": "{asset}", "conditionId": "0xc",
+                "size": 120.5, "avgPrice": 0.41, "initialValue": 49.405,
+                "currentValue": 72.3, "cashPnl": 22.895, "curPrice": 0.6,
+                "redeemable": {redeemable}, "mergeable": false, "outcome": "Yes",
+                "outcomeIndex": 0, "oppositeAsset": "999"}}"#
+        )
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_positions_are_paged_and_typed() {
+        let mut server = Server::new_async().await;
+        for (offset, assets) in [("0", vec![1, 2]), ("2", vec![3])] {
+            let body: Vec<String> = assets
+                .into_iter()
+                .map(|asset| position_json(asset, asset == 3))
+                .collect();
+            server
+                .mock("GET", "/positions")
+                .match_query(Matcher::AllOf(vec![
+                    Matcher::UrlEncoded("user".into(), USER.into()),
+                    Matcher::UrlEncoded("sizeThreshold".into(), "1".into()),
+                    Matcher::UrlEncoded("limit".into(), "2".into()),
+                    Matcher::UrlEncoded("offset".into(), offset.into()),
+                ]))
+                .with_body(format!("[{}]", body.join(",")))
+                .expect(1)
+                .create_async()
+                .await;
+        }
+        server
+            .mock("GET", "/value")
+            .match_query(Matcher::UrlEncoded("user".into(), USER.into()))
+            .with_body(format!(r#"[{{"user": "{USER}", "value": 216.9}}]"#))
+            .create_async()
+            .await;
+
+        let data =
+            DataApiClient::with_host(&ClobClient::new(&server.url()), &server.url()).unwrap();
+        let params = PositionParams {
+            size_threshold: Some(dec!(1)),
+            limit: Some(2),
+            ..Default::default()
+        };
+        let positions = data.get_positions(USER, Some(&params)).await.unwrap();
+        assert_eq!(positions.len(), 3);
+        assert_eq!(positions[0].size, dec!(120.5));
+        assert_eq!(positions[0].avg_price, dec!(0.41));
+        assert_eq!(positions[0].current_value, dec!(72.3));
+        assert_eq!(positions[0].opposite_asset.as_deref(), Some("999"));
+        let redeemable: Vec<&str> = positions
+            .iter()
+            .filter(|p| p.redeemable)
+            .map(|p| p.asset.as_str())
+            .collect();
+        assert_eq!(redeemable, vec!["3"]);
+
+        assert_eq!(
+            data.get_portfolio_value(USER, &[]).await.unwrap(),
+            dec!(216.9)
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_activity_and_holders() {
+        let mut server = Server::new_async().await;
+        server
+            .mock("GET", "/activity")
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("type".into(), "TRADE,REDEEM".into()),
+                Matcher::UrlEncoded("offset".into(), "0".into()),
+            ]))
+            .with_body(format!(
+                r#"[{{"proxyWallet": "{USER}", "timestamp": 1700000000, "conditionId": "0xc",
+                     "type": "TRADE", "size": 10, "usdcSize": 4.1, "price": 0.41,
+                     "asset": "1", "side": "BUY"}},
+                    {{"proxyWallet": "{USER}", "timestamp": 1690000000, "conditionId": "0xc",
+                     "type": "REDEEM", "size": 5, "usdcSize": 5, "asset": "", "side": ""}}]"#
+            ))
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/holders")
+            .match_query(Matcher::UrlEncoded("market".into(), "0xc".into()))
+            .with_body(format!(
+                r#"[{{"token": "1", "holders": [{{"proxyWallet": "{USER}", "amount": 120.5,
+                     "outcomeIndex": 0, "pseudonym": "Quiet-Heron"}}]}}]"#
+            ))
+            .create_async()
+            .await;
+
+        let data =
+            DataApiClient::with_host(&ClobClient::new(&server.url()), &server.url()).unwrap();
+        let params = ActivityParams {
+            types: vec![ActivityType::Trade, ActivityType::Redeem],
+            ..Default::default()
+        };
+        let activity = data.get_activity(USER, Some(&params)).await.unwrap();
+        assert_eq!(activity.len(), 2);
+        assert_eq!(activity[0].side, Some(Side::BUY));
+        assert_eq!(activity[0].usdc_size, dec!(4.1));
+        assert_eq!(activity[1].activity_type, ActivityType::Redeem);
+        assert_eq!(activity[1].asset, None);
+        assert_eq!(activity[1].side, None);
+
+        let holders = data.get_top_holders("0xc", Some(10)).await.unwrap();
+        assert_eq!(holders[0].token, "1");
+        assert_eq!(holders[0].holders[0].amount, dec!(120.5));
+    }
+
+    #[test]
+    fn test_new_activity_types_parse_as_unknown() {
+        let activity_type: ActivityType = serde_json::from_str(r#""YIELD""#).unwrap();
+        assert_eq!(activity_type, ActivityType::Unknown);
+        assert_eq!(
+            serde_json::from_str::<ActivityType>(r#""MERGE""#).unwrap(),
+            ActivityType::Merge
+        );
+    }
+}
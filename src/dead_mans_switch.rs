@@ -0,0 +1,479 @@
+//! Cancel-on-disconnect dead man's switch
+//!
+//! Resting orders stay live on the exchange when the process that placed
+//! them goes dark. The switch watches the client's keep-alive pings and any
+//! number of stream heartbeats, and cancels orders once either has been
+//! silent for longer than a threshold, or when the process is asked to
+//! shut down.
+//!
+//! Listening for shutdown signals takes them over for the rest of the
+//! process: once the switch has seen SIGINT/SIGTERM, the default handler that
+//! would have terminated the process never runs. After cancelling, the
+//! switch therefore either exits the process itself or, with
+//! `exit_on_shutdown` off, reports the signal through
+//! [`DeadMansSwitch::shutdown_requested`] for the application to act on.
+
+use crate::client::ClobClient;
+use crate::connection_manager::ConnectionManager;
+use crate::errors::Result;
+use crate::stream::StreamHeartbeat;
+use crate::types::CancelResponse;
+use crate::utils::time::now_millis;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tracing::{error, info, warn};
+
+/// Which orders the switch cancels when it fires
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CancelScope {
+    /// Every open order on the account, via `cancel_all`
+    All,
+    /// Only orders posted through this client, as tracked by its order registry
+    Session,
+}
+
+/// Dead man's switch configuration
+#[derive(Debug, Clone)]
+pub struct DeadMansSwitchConfig {
+    /// How long a watched connection may stay silent before the switch fires
+    ///
+    /// The keep-alive connection is only heard from once per ping, so it is
+    /// allowed its keep-alive interval on top of this.
+    pub threshold: Duration,
+    /// How often health is checked
+    pub check_interval: Duration,
+    pub scope: CancelScope,
+    /// Also fire on SIGINT/SIGTERM (Ctrl-C on other platforms)
+    pub cancel_on_shutdown: bool,
+    /// Exit the process once orders are cancelled on a shutdown signal
+    ///
+    /// Exits with the conventional `128 + signal` status. Turn this off to
+    /// run your own cleanup; the application must then wait on
+    /// [`DeadMansSwitch::shutdown_requested`] and exit itself, since the
+    /// signal no longer terminates the process.
+    pub exit_on_shutdown: bool,
+}
+
+impl Default for DeadMansSwitchConfig {
+    fn default() -> Self {
+        Self {
+            threshold: Duration::from_secs(10),
+            check_interval: Duration::from_secs(1),
+            scope: CancelScope::All,
+            cancel_on_shutdown: true,
+            exit_on_shutdown: true,
+        }
+    }
+}
+
+/// Why the switch fired
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TriggerReason {
+    /// Keep-alive pings to the exchange have failed for this long
+    ConnectionUnhealthy { silent_for: Duration },
+    /// A watched stream has received nothing for this long
+    StreamUnhealthy { silent_for: Duration },
+    /// The process received a shutdown signal
+    Shutdown,
+    /// Fired by hand through [`DeadMansSwitch::fire`]
+    Manual,
+}
+
+/// Cancels orders when the connection to the exchange goes quiet
+pub struct DeadMansSwitch {
+    client: Arc<ClobClient>,
+    config: DeadMansSwitchConfig,
+    connection: Option<Arc<ConnectionManager>>,
+    streams: Vec<StreamHeartbeat>,
+    /// Unix millis the switch was last armed; silence is measured from here at most
+    armed_at: AtomicU64,
+    armed: AtomicBool,
+    last_trigger: Mutex<Option<TriggerReason>>,
+    /// Set once a shutdown signal has been handled
+    shutdown: tokio::sync::watch::Sender<bool>,
+}
+
+impl DeadMansSwitch {
+    /// Create a switch that watches the client's keep-alive connection
+    ///
+    /// The connection only counts while its keep-alive task is running; start
+    /// it with [`ClobClient::start_keepalive`].
+    pub fn new(client: Arc<ClobClient>, config: DeadMansSwitchConfig) -> Self {
+        let connection = client.connection_manager().cloned();
+        Self {
+            client,
+            config,
+            connection,
+            streams: Vec::new(),
+            armed_at: AtomicU64::new(now_millis()),
+            armed: AtomicBool::new(true),
+            last_trigger: Mutex::new(None),
+            shutdown: tokio::sync::watch::channel(false).0,
+        }
+    }
+
+    /// Also fire when this stream stops receiving frames
+    pub fn watch_stream(mut self, heartbeat: StreamHeartbeat) -> Self {
+        self.streams.push(heartbeat);
+        self
+    }
+
+    /// Watch a different keep-alive connection, or none
+    pub fn watch_connection(mut self, connection: Option<Arc<ConnectionManager>>) -> Self {
+        self.connection = connection;
+        self
+    }
+
+    /// Whether the switch will fire on the next unhealthy check
+    pub fn is_armed(&self) -> bool {
+        self.armed.load(Ordering::Relaxed)
+    }
+
+    /// The reason the switch last fired
+    pub fn last_trigger(&self) -> Option<TriggerReason> {
+        self.last_trigger.lock().unwrap().clone()
+    }
+
+    /// Resolve once the switch has handled a shutdown signal
+    ///
+    /// Orders have been cancelled by the time this resolves. Only reached
+    /// when `exit_on_shutdown` is off; the process should exit afterwards.
+    pub async fn shutdown_requested(&self) {
+        let mut shutdown = self.shutdown.subscribe();
+        let _ = shutdown.wait_for(|requested| *requested).await;
+    }
+
+    /// The first watched source that has been silent longer than the threshold
+    ///
+    /// The keep-alive connection counts as silent only once it has also missed
+    /// a ping, past the threshold plus its keep-alive interval.
+    pub fn unhealthy_reason(&self) -> Option<TriggerReason> {
+        let now = now_millis();
+        let armed_at = self.armed_at.load(Ordering::Relaxed);
+        let silent_for = |last: Option<u64>| {
+            let since = last.unwrap_or(0).max(armed_at);
+            Duration::from_millis(now.saturating_sub(since))
+        };
+
+        if let Some(connection) = self.connection.as_ref().filter(|c| c.is_running()) {
+            let silent_for = silent_for(connection.last_success_millis());
+            let limit = self.config.threshold + connection.keepalive_interval().unwrap_or_default();
+            if silent_for > limit {
+                return Some(TriggerReason::ConnectionUnhealthy { silent_for });
+            }
+        }
+
+        self.streams.iter().find_map(|heartbeat| {
+            let silent_for = silent_for(heartbeat.last_beat_millis());
+            (silent_for > self.config.threshold)
+                .then_some(TriggerReason::StreamUnhealthy { silent_for })
+        })
+    }
+
+    /// Check health once, firing if a source has been silent too long
+    ///
+    /// The switch fires once per outage and re-arms when every watched
+    /// source is healthy again. Returns the trigger if it fired.
+    pub async fn check(&self) -> Result<Option<TriggerReason>> {
+        match self.unhealthy_reason() {
+            Some(reason) if self.armed.swap(false, Ordering::Relaxed) => {
+                self.fire(reason.clone()).await?;
+                Ok(Some(reason))
+            },
+            Some(_) => Ok(None),
+            None => {
+                if !self.armed.swap(true, Ordering::Relaxed) {
+                    info!("Dead man's switch re-armed");
+                    self.armed_at.store(now_millis(), Ordering::Relaxed);
+                }
+                Ok(None)
+            },
+        }
+    }
+
+    /// Cancel orders in the configured scope
+    pub async fn fire(&self, reason: TriggerReason) -> Result<CancelResponse> {
+        warn!("Dead man's switch fired: {:?}", reason);
+        *self.last_trigger.lock().unwrap() = Some(reason);
+
+        match self.config.scope {
+            CancelScope::All => self.client.cancel_all().await,
+            CancelScope::Session => {
+                let order_ids: Vec<String> = self
+                    .client
+                    .order_registry()
+                    .orders()
+                    .into_iter()
+                    .map(|order| order.order_id)
+                    .collect();
+                if order_ids.is_empty() {
+                    return Ok(CancelResponse::default());
+                }
+                self.client.cancel_orders(&order_ids).await
+            },
+        }
+    }
+
+    /// Run the switch in the background until it fires on shutdown
+    ///
+    /// Shutdown signals are listened for from the moment this returns.
+    pub fn spawn(self: &Arc<Self>) -> JoinHandle<()> {
+        let switch = Arc::clone(self);
+        let shutdown = ShutdownSignal::listen(switch.config.cancel_on_shutdown);
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(switch.config.check_interval);
+            let shutdown = shutdown.recv();
+            tokio::pin!(shutdown);
+
+            loop {
+                tokio::select! {
+                    _ = ticker.tick() => {
+                        if let Err(e) = switch.check().await {
+                            error!("Dead man's switch failed to cancel orders: {}", e);
+                        }
+                    }
+                    exit_code = &mut shutdown => {
+                        if let Err(e) = switch.fire(TriggerReason::Shutdown).await {
+                            error!("Dead man's switch failed to cancel orders: {}", e);
+                        }
+                        if switch.config.exit_on_shutdown {
+                            info!("Exiting after shutdown signal");
+                            std::process::exit(exit_code);
+                        }
+                        switch.shutdown.send_replace(true);
+                        return;
+                    }
+                }
+            }
+        })
+    }
+}
+
+/// Shutdown signal listeners, registered as soon as they are created
+struct ShutdownSignal {
+    #[cfg(unix)]
+    listeners: Option<(tokio::signal::unix::Signal, tokio::signal::unix::Signal)>,
+    #[cfg(not(unix))]
+    enabled: bool,
+}
+
+impl ShutdownSignal {
+    /// Take over SIGINT and SIGTERM, or nothing if `enabled` is false
+    fn listen(enabled: bool) -> Self {
+        #[cfg(unix)]
+        {
+            use tokio::signal::unix::{signal, SignalKind};
+            if !enabled {
+                return Self { listeners: None };
+            }
+            match (
+                signal(SignalKind::interrupt()),
+                signal(SignalKind::terminate()),
+            ) {
+                (Ok(interrupt), Ok(terminate)) => Self {
+                    listeners: Some((interrupt, terminate)),
+                },
+                _ => {
+                    warn!("Could not listen for shutdown signals");
+                    Self { listeners: None }
+                },
+            }
+        }
+
+        #[cfg(not(unix))]
+        {
+            Self { enabled }
+        }
+    }
+
+    /// Resolve with the exit status for the signal received; never if disabled
+    async fn recv(self) -> i32 {
+        #[cfg(unix)]
+        {
+            let Some((mut interrupt, mut terminate)) = self.listeners else {
+                return std::future::pending().await;
+            };
+            tokio::select! {
+                _ = interrupt.recv() => 130,
+                _ = terminate.recv() => 143,
+            }
+        }
+
+        #[cfg(not(unix))]
+        {
+            if !self.enabled {
+                return std::future::pending().await;
+            }
+            let _ = tokio::signal::ctrl_c().await;
+            130
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::order_registry::TrackedOrder;
+    use crate::types::{ApiCredentials, OrderType, Side};
+    use mockito::{Matcher, Server};
+
+    fn create_test_client(base_url: &str) -> Arc<ClobClient> {
+        Arc::new(ClobClient::with_l2_headers(
+            base_url,
+            "0x1234567890123456789012345678901234567890123456789012345678901234",
+            137,
+            ApiCredentials {
+                api_key: "test_key".to_string(),
+                secret: "dGVzdF9zZWNyZXRfa2V5XzEyMzQ1".to_string(),
+                passphrase: "test_passphrase".to_string(),
+            },
+        ))
+    }
+
+    fn fast_config(scope: CancelScope) -> DeadMansSwitchConfig {
+        DeadMansSwitchConfig {
+            threshold: Duration::from_millis(50),
+            check_interval: Duration::from_millis(10),
+            scope,
+            cancel_on_shutdown: false,
+            exit_on_shutdown: false,
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_silent_stream_cancels_all_once() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("DELETE", "/cancel-all")
+            .with_status(200)
+            .with_body(r#"{"canceled": ["0xabc"], "not_canceled": {}}"#)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let heartbeat = StreamHeartbeat::new();
+        let switch = DeadMansSwitch::new(
+            create_test_client(&server.url()),
+            fast_config(CancelScope::All),
+        )
+        .watch_stream(heartbeat.clone());
+
+        heartbeat.beat();
+        assert_eq!(switch.check().await.unwrap(), None);
+
+        tokio::time::sleep(Duration::from_millis(80)).await;
+        let fired = switch.check().await.unwrap();
+        assert!(matches!(fired, Some(TriggerReason::StreamUnhealthy { .. })));
+        assert!(!switch.is_armed());
+
+        // Still silent: the switch does not fire twice for one outage
+        assert_eq!(switch.check().await.unwrap(), None);
+        mock.assert_async().await;
+
+        heartbeat.beat();
+        switch.check().await.unwrap();
+        assert!(switch.is_armed());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_running_keepalive_does_not_fire() {
+        let mut server = Server::new_async().await;
+        server
+            .mock("GET", "/time")
+            .with_status(200)
+            .with_body("1700000000")
+            .create_async()
+            .await;
+        let cancel = server
+            .mock("DELETE", "/cancel-all")
+            .with_status(200)
+            .with_body(r#"{"canceled": [], "not_canceled": {}}"#)
+            .expect(0)
+            .create_async()
+            .await;
+
+        let client = create_test_client(&server.url());
+        // Pings are further apart than the threshold
+        client.start_keepalive(Duration::from_millis(150)).await;
+        let switch = DeadMansSwitch::new(client.clone(), fast_config(CancelScope::All));
+
+        for _ in 0..50 {
+            assert_eq!(switch.check().await.unwrap(), None);
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        client.stop_keepalive().await;
+
+        cancel.assert_async().await;
+        assert!(switch.is_armed());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_session_scope_cancels_tracked_orders() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("DELETE", "/orders")
+            .match_body(Matcher::Json(serde_json::json!(["0xsession"])))
+            .with_status(200)
+            .with_body(r#"{"canceled": ["0xsession"], "not_canceled": {}}"#)
+            .create_async()
+            .await;
+
+        let client = create_test_client(&server.url());
+        client.order_registry().track(TrackedOrder {
+            order_id: "0xsession".to_string(),
+            token_id: "123".to_string(),
+            market: None,
+            side: Side::BUY,
+            order_type: OrderType::GTC,
+            expires_at: None,
+            posted_at: chrono::Utc::now(),
+        });
+
+        let switch = DeadMansSwitch::new(client.clone(), fast_config(CancelScope::Session));
+        let response = switch.fire(TriggerReason::Manual).await.unwrap();
+
+        mock.assert_async().await;
+        assert!(response.is_canceled("0xsession"));
+        assert!(client.order_registry().is_empty());
+        assert_eq!(switch.last_trigger(), Some(TriggerReason::Manual));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_shutdown_signal_cancels_and_reaches_application() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("DELETE", "/cancel-all")
+            .with_status(200)
+            .with_body(r#"{"canceled": [], "not_canceled": {}}"#)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let config = DeadMansSwitchConfig {
+            cancel_on_shutdown: true,
+            ..fast_config(CancelScope::All)
+        };
+        let switch = Arc::new(DeadMansSwitch::new(
+            create_test_client(&server.url()),
+            config,
+        ));
+        let handle = switch.spawn();
+
+        let status = std::process::Command::new("kill")
+            .args(["-TERM", &std::process::id().to_string()])
+            .status()
+            .unwrap();
+        assert!(status.success());
+
+        tokio::time::timeout(Duration::from_secs(5), switch.shutdown_requested())
+            .await
+            .expect("shutdown was not reported to the application");
+        handle.await.unwrap();
+
+        mock.assert_async().await;
+        assert_eq!(switch.last_trigger(), Some(TriggerReason::Shutdown));
+    }
+}
@@ -305,6 +305,59 @@ impl Decoder<FillEvent> for RawTradeResponse {
     }
 }
 
+/// Converts a trade into a fill of the authenticated user's order.
+///
+/// `maker_address` on a trade is the user's own funder address, so which
+/// side of the fill it lands on depends on `trader_side`. On maker-side
+/// trades the taker's address is not reported and is left as zero.
+impl Decoder<FillEvent> for Trade {
+    fn decode(&self) -> Result<FillEvent> {
+        let timestamp = DateTime::from_timestamp(self.match_time as i64, 0)
+            .ok_or_else(|| PolyfillError::parse("Invalid trade timestamp".to_string(), None))?;
+
+        let own_address = fast_parse::parse_address(&self.maker_address)?;
+        let mut fill = FillEvent {
+            id: self.id.clone(),
+            order_id: self.taker_order_id.clone(),
+            token_id: self.asset_id.clone(),
+            side: self.side,
+            price: self.price,
+            size: self.size,
+            timestamp,
+            maker_address: Address::ZERO,
+            taker_address: own_address,
+            fee: self.fee(),
+        };
+
+        match self.trader_side {
+            Some(TraderSide::MAKER) => {
+                fill.maker_address = own_address;
+                fill.taker_address = Address::ZERO;
+                // The trade-level fields describe the taker; the user's own
+                // share is reported on their maker order
+                if let Some(maker_order) = self
+                    .maker_orders
+                    .iter()
+                    .find(|maker_order| maker_order.owner == self.owner)
+                {
+                    fill.order_id = maker_order.order_id.clone();
+                    fill.side = maker_order.side.unwrap_or_else(|| self.side.opposite());
+                    fill.price = maker_order.price;
+                    fill.size = maker_order.matched_amount;
+                    fill.fee = maker_order.fee();
+                }
+            },
+            Some(TraderSide::TAKER) | None => {
+                if let Some(maker_order) = self.maker_orders.first() {
+                    fill.maker_address = fast_parse::parse_address(&maker_order.maker_address)?;
+                }
+            },
+        }
+
+        Ok(fill)
+    }
+}
+
 impl Decoder<Market> for RawMarketResponse {
     fn decode(&self) -> Result<Market> {
         let tokens = [
@@ -572,4 +625,51 @@ mod tests {
         let results: Vec<serde_json::Value> = decoder.parse_json_stream(data).unwrap();
         assert_eq!(results.len(), 2);
     }
+
+    #[test]
+    fn test_maker_side_trade_decodes_to_own_maker_fill() {
+        let trade: Trade = serde_json::from_str(
+            r#"{
+                "id": "trade-1", "taker_order_id": "0xtaker", "market": "0xm",
+                "asset_id": "123", "side": "SELL", "size": "100", "fee_rate_bps": "0",
+                "price": "0.5", "status": "MATCHED", "match_time": "1700000000",
+                "outcome": "Yes", "bucket_index": 0, "owner": "test_key",
+                "maker_address": "0x1111111111111111111111111111111111111111",
+                "maker_orders": [{
+                    "order_id": "0xother", "owner": "other_key",
+                    "maker_address": "0x2222222222222222222222222222222222222222",
+                    "matched_amount": "40", "price": "0.5", "fee_rate_bps": "0",
+                    "asset_id": "123", "outcome": "Yes", "side": "BUY"
+                }, {
+                    "order_id": "0xmine", "owner": "test_key",
+                    "maker_address": "0x1111111111111111111111111111111111111111",
+                    "matched_amount": "60", "price": "0.48", "fee_rate_bps": "100",
+                    "asset_id": "123", "outcome": "Yes", "side": "BUY"
+                }],
+                "trader_side": "MAKER"
+            }"#,
+        )
+        .unwrap();
+
+        let fill: FillEvent = trade.decode().unwrap();
+        assert_eq!(fill.order_id, "0xmine");
+        assert_eq!(
+            fill.maker_address,
+            Address::from_str("0x1111111111111111111111111111111111111111").unwrap()
+        );
+        assert_eq!(fill.taker_address, Address::ZERO);
+        assert_eq!(fill.side, Side::BUY);
+        assert_eq!(fill.size, Decimal::from(60));
+        assert_eq!(fill.price, Decimal::from_str("0.48").unwrap());
+        // 60 * 0.48 * 100bps
+        assert_eq!(fill.fee, Decimal::from_str("0.288").unwrap());
+    }
+
+    #[test]
+    fn test_unknown_trade_status_deserializes() {
+        let status: TradeStatus = serde_json::from_str("\"SETTLING\"").unwrap();
+        assert_eq!(status, TradeStatus::Unknown);
+        assert_eq!(status.as_str(), "UNKNOWN");
+        assert!(!status.is_final());
+    }
 }
@@ -0,0 +1,137 @@
+//! Dry-run mode for mutating endpoints
+//!
+//! A client in dry-run mode builds, signs and authenticates order posts,
+//! cancels and balance updates exactly as it would live, then records the
+//! request in a [`DryRunLog`] instead of sending it. Reads still go to the
+//! exchange, so a strategy can run against production data without risking
+//! capital.
+
+use crate::errors::{PolyfillError, Result};
+use chrono::{DateTime, Utc};
+use reqwest::Request;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// A request the client would have sent
+#[derive(Debug, Clone, PartialEq)]
+pub struct DryRunRequest {
+    pub method: String,
+    /// URL path, e.g. `/order`
+    pub path: String,
+    pub query: Option<String>,
+    /// Request headers, including the L2 `POLY_*` authentication headers
+    pub headers: HashMap<String, String>,
+    /// JSON body, if the request had one
+    pub body: Option<Value>,
+    pub recorded_at: DateTime<Utc>,
+}
+
+impl DryRunRequest {
+    /// Capture a fully built request
+    pub fn from_request(request: &Request) -> Result<Self> {
+        let headers = request
+            .headers()
+            .iter()
+            .map(|(name, value)| {
+                let value = value
+                    .to_str()
+                    .map_err(|e| PolyfillError::internal(format!("Invalid {} header", name), e))?;
+                Ok((name.as_str().to_string(), value.to_string()))
+            })
+            .collect::<Result<HashMap<_, _>>>()?;
+
+        let body = request
+            .body()
+            .and_then(|body| body.as_bytes())
+            .map(serde_json::from_slice)
+            .transpose()?;
+
+        Ok(Self {
+            method: request.method().to_string(),
+            path: request.url().path().to_string(),
+            query: request.url().query().map(str::to_string),
+            headers,
+            body,
+            recorded_at: Utc::now(),
+        })
+    }
+
+    /// Value of a header, matched case-insensitively
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .get(&name.to_ascii_lowercase())
+            .map(String::as_str)
+    }
+}
+
+/// Requests recorded by a client in dry-run mode, oldest first
+#[derive(Debug, Default)]
+pub struct DryRunLog {
+    requests: Mutex<Vec<DryRunRequest>>,
+}
+
+impl DryRunLog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&self, request: DryRunRequest) {
+        self.requests.lock().unwrap().push(request);
+    }
+
+    /// Every recorded request
+    pub fn requests(&self) -> Vec<DryRunRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    /// The most recently recorded request
+    pub fn last(&self) -> Option<DryRunRequest> {
+        self.requests.lock().unwrap().last().cloned()
+    }
+
+    /// Remove and return every recorded request
+    pub fn take(&self) -> Vec<DryRunRequest> {
+        std::mem::take(&mut *self.requests.lock().unwrap())
+    }
+
+    pub fn clear(&self) {
+        self.requests.lock().unwrap().clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.requests.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_request_captures_headers_and_body() {
+        let request = reqwest::Client::new()
+            .delete("https://clob.example.com/orders?dry=1")
+            .header("POLY_API_KEY", "test_key")
+            .json(&["0xabc"])
+            .build()
+            .unwrap();
+
+        let recorded = DryRunRequest::from_request(&request).unwrap();
+        assert_eq!(recorded.method, "DELETE");
+        assert_eq!(recorded.path, "/orders");
+        assert_eq!(recorded.query.as_deref(), Some("dry=1"));
+        assert_eq!(recorded.header("POLY_API_KEY"), Some("test_key"));
+        assert_eq!(recorded.body, Some(serde_json::json!(["0xabc"])));
+
+        let log = DryRunLog::new();
+        log.record(recorded.clone());
+        assert_eq!(log.last(), Some(recorded));
+        assert_eq!(log.take().len(), 1);
+        assert!(log.is_empty());
+    }
+}
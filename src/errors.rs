@@ -104,6 +104,29 @@ pub enum AuthErrorKind {
     InsufficientPermissions,
     SignatureError,
     NonceError,
+    /// The local clock is too far from the exchange's for signed timestamps to be accepted
+    ClockSkew,
+}
+
+impl AuthErrorKind {
+    /// Classify a 401/403 response from the exchange
+    pub fn from_response(status: u16, message: &str) -> Self {
+        let message = message.to_lowercase();
+
+        if message.contains("nonce") {
+            AuthErrorKind::NonceError
+        } else if message.contains("timestamp") {
+            AuthErrorKind::ClockSkew
+        } else if message.contains("expired") {
+            AuthErrorKind::ExpiredCredentials
+        } else if message.contains("signature") || message.contains("hmac") {
+            AuthErrorKind::SignatureError
+        } else if status == 403 {
+            AuthErrorKind::InsufficientPermissions
+        } else {
+            AuthErrorKind::InvalidCredentials
+        }
+    }
 }
 
 /// Order error subcategories
@@ -119,6 +142,56 @@ pub enum OrderErrorKind {
     ExecutionFailed,
     SizeConstraint,
     PriceConstraint,
+    InvalidExpiration,
+    NotFilled,
+    /// A post-only order would have crossed the spread
+    WouldCross,
+}
+
+impl OrderErrorKind {
+    /// Classify an order rejection message returned by the exchange
+    ///
+    /// The exchange reports rejections either as an error code
+    /// (`INVALID_ORDER_MIN_SIZE`) or as a human readable `errorMsg`, so both
+    /// forms are matched.
+    pub fn from_exchange_message(message: &str) -> Self {
+        let msg = message.to_ascii_lowercase();
+
+        if msg.contains("tick size") || msg.contains("min_tick_size") {
+            OrderErrorKind::InvalidPrice
+        } else if msg.contains("min_size") || msg.contains("lower than the minimum") {
+            OrderErrorKind::SizeConstraint
+        } else if msg.contains("duplicated") {
+            OrderErrorKind::DuplicateOrder
+        } else if msg.contains("balance") || msg.contains("allowance") {
+            OrderErrorKind::InsufficientBalance
+        } else if msg.contains("expiration") {
+            OrderErrorKind::InvalidExpiration
+        } else if msg.contains("post-only") || msg.contains("post only") || msg.contains("crosses")
+        {
+            OrderErrorKind::WouldCross
+        } else if msg.contains("fok") || msg.contains("fully filled") {
+            OrderErrorKind::NotFilled
+        } else if msg.contains("not yet ready")
+            || msg.contains("market_not_ready")
+            || msg.contains("closed only")
+        {
+            OrderErrorKind::MarketClosed
+        } else {
+            OrderErrorKind::ExecutionFailed
+        }
+    }
+
+    /// Classify the reason the exchange gave for not canceling an order
+    pub fn from_cancel_reason(reason: &str) -> Self {
+        let reason = reason.to_ascii_lowercase();
+
+        if reason.contains("not found") || reason.contains("can't be found") {
+            OrderErrorKind::OrderNotFound
+        } else {
+            OrderErrorKind::CancellationFailed
+        }
+    }
 }
 
 /// Market data error subcategories
@@ -234,6 +307,43 @@ impl PolyfillError {
         }
     }
 
+    /// Build an error from a non-success HTTP response
+    ///
+    /// If `body` is a JSON object with an `error` field, that field is used as
+    /// the message and, for API errors, as the `error_code`.
+    pub fn from_response(
+        status: u16,
+        context: &str,
+        body: &str,
+        retry_after: Option<Duration>,
+    ) -> Self {
+        let error_field = serde_json::from_str::<serde_json::Value>(body)
+            .ok()
+            .and_then(|json| json.get("error").and_then(|e| e.as_str()).map(String::from));
+        let detail = match (&error_field, body.trim()) {
+            (Some(error), _) => error.clone(),
+            (None, "") => format!("HTTP {}", status),
+            (None, body) => body.to_string(),
+        };
+        let message = format!("{}: {}", context, detail);
+
+        match status {
+            429 => Self::RateLimit {
+                message,
+                retry_after,
+            },
+            401 | 403 => Self::Auth {
+                kind: AuthErrorKind::from_response(status, &detail),
+                message,
+            },
+            _ => Self::Api {
+                status,
+                message,
+                error_code: error_field,
+            },
+        }
+    }
+
     pub fn auth(message: impl Into<String>) -> Self {
         Self::Auth {
             message: message.into(),
@@ -306,6 +416,14 @@ impl PolyfillError {
         }
     }
 
+    /// Validation error for a specific field
+    pub fn validation_field(message: impl Into<String>, field: impl Into<String>) -> Self {
+        Self::Validation {
+            message: message.into(),
+            field: Some(field.into()),
+        }
+    }
+
     pub fn internal<E: std::error::Error + Send + Sync + 'static>(
         message: impl Into<String>,
         source: E,
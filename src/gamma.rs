@@ -0,0 +1,633 @@
+//! Gamma markets API: events, markets, tags, series and search
+//!
+//! Gamma is Polymarket's market discovery service. Unlike the CLOB's
+//! `get_markets`, it groups markets into events and series and reports tags,
+//! volume and liquidity. Every [`GammaMarket`] carries the CLOB
+//! `condition_id` and outcome token IDs, so a market found here can be traded
+//! through [`ClobClient`] directly, or resolved to its CLOB [`Market`] with
+//! [`GammaClient::clob_market`].
+//!
+//! [`GammaClient`] sends its requests through a [`ClobClient`] built with
+//! [`ClobClientBuilder::shared_with`](crate::ClobClientBuilder::shared_with),
+//! so it reuses the connection pool, retry policy and metrics machinery. List
+//! endpoints are offset based; they are exposed as [`Paginator`]s whose cursor
+//! is the offset of the next page.
+
+use crate::client::ClobClient;
+use crate::errors::Result;
+use crate::pagination::Paginator;
+use crate::types::Market;
+use chrono::{DateTime, Utc};
+use futures::TryStreamExt;
+use rust_decimal::Decimal;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Deserializer};
+use serde_json::Value;
+
+/// Production Gamma host
+pub const GAMMA_HOST: &str = "https://gamma-api.polymarket.com";
+
+/// Page size used when the query does not set a limit
+pub const DEFAULT_PAGE_SIZE: u32 = 100;
+
+/// A market as listed by Gamma
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GammaMarket {
+    pub id: String,
+    pub question: String,
+    /// CLOB condition ID, as taken by [`ClobClient::get_market`]
+    pub condition_id: String,
+    #[serde(default)]
+    pub slug: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Outcome names, in the same order as `clob_token_ids`
+    #[serde(default, deserialize_with = "string_encoded_list")]
+    pub outcomes: Vec<String>,
+    #[serde(default, deserialize_with = "string_encoded_list")]
+    pub outcome_prices: Vec<Decimal>,
+    /// CLOB token ID of each outcome
+    #[serde(default, deserialize_with = "string_encoded_list")]
+    pub clob_token_ids: Vec<String>,
+    #[serde(default)]
+    pub volume: Option<Decimal>,
+    #[serde(default)]
+    pub volume_24hr: Option<Decimal>,
+    #[serde(default)]
+    pub liquidity: Option<Decimal>,
+    #[serde(default)]
+    pub best_bid: Option<Decimal>,
+    #[serde(default)]
+    pub best_ask: Option<Decimal>,
+    #[serde(default)]
+    pub last_trade_price: Option<Decimal>,
+    #[serde(default)]
+    pub order_price_min_tick_size: Option<Decimal>,
+    #[serde(default)]
+    pub order_min_size: Option<Decimal>,
+    #[serde(default)]
+    pub start_date: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub end_date: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub active: bool,
+    #[serde(default)]
+    pub closed: bool,
+    #[serde(default)]
+    pub archived: bool,
+    #[serde(default)]
+    pub enable_order_book: bool,
+    #[serde(default)]
+    pub neg_risk: bool,
+    #[serde(default, deserialize_with = "null_as_empty")]
+    pub tags: Vec<GammaTag>,
+}
+
+/// One outcome of a [`GammaMarket`] and the CLOB token that trades it
+#[derive(Debug, Clone, PartialEq)]
+pub struct GammaOutcome {
+    pub outcome: String,
+    pub token_id: String,
+    /// Last price Gamma reported, if any
+    pub price: Option<Decimal>,
+}
+
+impl GammaMarket {
+    /// Outcomes paired with their CLOB token IDs
+    pub fn outcome_tokens(&self) -> Vec<GammaOutcome> {
+        self.outcomes
+            .iter()
+            .zip(&self.clob_token_ids)
+            .enumerate()
+            .map(|(i, (outcome, token_id))| GammaOutcome {
+                outcome: outcome.clone(),
+                token_id: token_id.clone(),
+                price: self.outcome_prices.get(i).copied(),
+            })
+            .collect()
+    }
+
+    /// CLOB token ID of the outcome named `outcome`, ignoring case
+    pub fn token_id(&self, outcome: &str) -> Option<&str> {
+        self.outcomes
+            .iter()
+            .position(|name| name.eq_ignore_ascii_case(outcome))
+            .and_then(|i| self.clob_token_ids.get(i))
+            .map(String::as_str)
+    }
+
+    /// Whether the market can currently be traded on the CLOB
+    pub fn is_tradable(&self) -> bool {
+        self.active && !self.closed && self.enable_order_book && !self.clob_token_ids.is_empty()
+    }
+}
+
+/// A group of related markets, e.g. every candidate of one election
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GammaEvent {
+    pub id: String,
+    #[serde(default)]
+    pub ticker: Option<String>,
+    #[serde(default)]
+    pub slug: Option<String>,
+    pub title: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub start_date: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub end_date: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub active: bool,
+    #[serde(default)]
+    pub closed: bool,
+    #[serde(default)]
+    pub archived: bool,
+    #[serde(default)]
+    pub neg_risk: bool,
+    #[serde(default)]
+    pub volume: Option<Decimal>,
+    #[serde(default)]
+    pub volume_24hr: Option<Decimal>,
+    #[serde(default)]
+    pub liquidity: Option<Decimal>,
+    #[serde(default)]
+    pub open_interest: Option<Decimal>,
+    #[serde(default, deserialize_with = "null_as_empty")]
+    pub markets: Vec<GammaMarket>,
+    #[serde(default, deserialize_with = "null_as_empty")]
+    pub tags: Vec<GammaTag>,
+    #[serde(default, deserialize_with = "null_as_empty")]
+    pub series: Vec<GammaSeries>,
+}
+
+impl GammaEvent {
+    /// Condition IDs of the event's markets
+    pub fn condition_ids(&self) -> impl Iterator<Item = &str> + '_ {
+        self.markets.iter().map(|m| m.condition_id.as_str())
+    }
+
+    /// CLOB token IDs of every outcome of every market in the event
+    pub fn token_ids(&self) -> impl Iterator<Item = &str> + '_ {
+        self.markets
+            .iter()
+            .flat_map(|m| m.clob_token_ids.iter().map(String::as_str))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GammaTag {
+    pub id: String,
+    pub label: String,
+    #[serde(default)]
+    pub slug: Option<String>,
+}
+
+/// A recurring family of events, e.g. daily price markets
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GammaSeries {
+    pub id: String,
+    #[serde(default)]
+    pub ticker: Option<String>,
+    #[serde(default)]
+    pub slug: Option<String>,
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub series_type: Option<String>,
+    #[serde(default)]
+    pub recurrence: Option<String>,
+    #[serde(default)]
+    pub active: bool,
+    #[serde(default)]
+    pub closed: bool,
+    #[serde(default)]
+    pub volume: Option<Decimal>,
+    #[serde(default)]
+    pub liquidity: Option<Decimal>,
+    #[serde(default, deserialize_with = "null_as_empty")]
+    pub events: Vec<GammaEvent>,
+}
+
+/// Results of a free-text search
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct GammaSearchResults {
+    #[serde(default, deserialize_with = "null_as_empty")]
+    pub events: Vec<GammaEvent>,
+    #[serde(default, deserialize_with = "null_as_empty")]
+    pub tags: Vec<GammaTag>,
+    /// User profiles, left untyped
+    #[serde(default, deserialize_with = "null_as_empty")]
+    pub profiles: Vec<Value>,
+}
+
+/// Filters for listing markets
+#[derive(Debug, Clone, Default)]
+pub struct GammaMarketParams {
+    pub active: Option<bool>,
+    pub closed: Option<bool>,
+    pub archived: Option<bool>,
+    pub tag_id: Option<String>,
+    pub slugs: Vec<String>,
+    pub condition_ids: Vec<String>,
+    pub clob_token_ids: Vec<String>,
+    /// Field to sort by, e.g. `volume24hr`
+    pub order: Option<String>,
+    pub ascending: Option<bool>,
+    /// Page size, [`DEFAULT_PAGE_SIZE`] if unset
+    pub limit: Option<u32>,
+}
+
+impl GammaMarketParams {
+    pub fn to_query_params(&self) -> Vec<(&'static str, String)> {
+        let mut params = Vec::new();
+
+        if let Some(x) = self.active {
+            params.push(("active", x.to_string()));
+        }
+        if let Some(x) = self.closed {
+            params.push(("closed", x.to_string()));
+        }
+        if let Some(x) = self.archived {
+            params.push(("archived", x.to_string()));
+        }
+        if let Some(x) = &self.tag_id {
+            params.push(("tag_id", x.clone()));
+        }
+        params.extend(self.slugs.iter().map(|x| ("slug", x.clone())));
+        params.extend(
+            self.condition_ids
+                .iter()
+                .map(|x| ("condition_ids", x.clone())),
+        );
+        params.extend(
+            self.clob_token_ids
+                .iter()
+                .map(|x| ("clob_token_ids", x.clone())),
+        );
+        if let Some(x) = &self.order {
+            params.push(("order", x.clone()));
+        }
+        if let Some(x) = self.ascending {
+            params.push(("ascending", x.to_string()));
+        }
+        params
+    }
+}
+
+/// Filters for listing events
+#[derive(Debug, Clone, Default)]
+pub struct GammaEventParams {
+    pub active: Option<bool>,
+    pub closed: Option<bool>,
+    pub archived: Option<bool>,
+    pub tag_id: Option<String>,
+    pub series_id: Option<String>,
+    pub slug: Option<String>,
+    /// Field to sort by, e.g. `volume24hr`
+    pub order: Option<String>,
+    pub ascending: Option<bool>,
+    /// Page size, [`DEFAULT_PAGE_SIZE`] if unset
+    pub limit: Option<u32>,
+}
+
+impl GammaEventParams {
+    pub fn to_query_params(&self) -> Vec<(&'static str, String)> {
+        let mut params = Vec::new();
+
+        if let Some(x) = self.active {
+            params.push(("active", x.to_string()));
+        }
+        if let Some(x) = self.closed {
+            params.push(("closed", x.to_string()));
+        }
+        if let Some(x) = self.archived {
+            params.push(("archived", x.to_string()));
+        }
+        if let Some(x) = &self.tag_id {
+            params.push(("tag_id", x.clone()));
+        }
+        if let Some(x) = &self.series_id {
+            params.push(("series_id", x.clone()));
+        }
+        if let Some(x) = &self.slug {
+            params.push(("slug", x.clone()));
+        }
+        if let Some(x) = &self.order {
+            params.push(("order", x.clone()));
+        }
+        if let Some(x) = self.ascending {
+            params.push(("ascending", x.to_string()));
+        }
+        params
+    }
+}
+
+/// Client for the Gamma markets API
+pub struct GammaClient {
+    client: ClobClient,
+}
+
+impl GammaClient {
+    /// Client for the production Gamma host, sharing `clob`'s transport
+    pub fn new(clob: &ClobClient) -> Result<Self> {
+        Self::with_host(clob, GAMMA_HOST)
+    }
+
+    /// Client for a Gamma-compatible `host`, sharing `clob`'s transport
+    ///
+    /// The connection pool, DNS cache and market cache are shared. Gamma's
+    /// rate limits are separate from the CLOB's, so `clob`'s rate limiter is
+    /// not.
+    pub fn with_host(clob: &ClobClient, host: &str) -> Result<Self> {
+        let client = ClobClient::builder(host)
+            .chain_id(clob.chain_id())
+            .shared_with(clob)
+            .rate_limiter(None)
+            .build()?;
+        Ok(Self { client })
+    }
+
+    /// Client the requests are sent through, e.g. for its metrics
+    pub fn transport(&self) -> &ClobClient {
+        &self.client
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.client.base_url
+    }
+
+    /// Build a paginator over an offset-based list endpoint
+    fn paginate<T: DeserializeOwned + Send + 'static>(
+        &self,
+        path: &'static str,
+        query_params: Vec<(&'static str, String)>,
+        limit: Option<u32>,
+        offset: Option<u32>,
+    ) -> Paginator<'_, T> {
+        self.client.paginate_offsets(
+            path,
+            query_params,
+            offset.unwrap_or(0),
+            limit.unwrap_or(DEFAULT_PAGE_SIZE),
+        )
+    }
+
+    /// Stream markets page by page, starting at `offset`
+    pub fn stream_markets(
+        &self,
+        params: Option<&GammaMarketParams>,
+        offset: Option<u32>,
+    ) -> Paginator<'_, GammaMarket> {
+        let params = params.cloned().unwrap_or_default();
+        self.paginate("/markets", params.to_query_params(), params.limit, offset)
+    }
+
+    /// Every market matching `params`
+    pub async fn get_markets(
+        &self,
+        params: Option<&GammaMarketParams>,
+    ) -> Result<Vec<GammaMarket>> {
+        self.stream_markets(params, None).try_collect().await
+    }
+
+    /// Market by its Gamma ID
+    pub async fn get_market(&self, id: &str) -> Result<GammaMarket> {
+        self.client.get_json(&format!("/markets/{}", id), &[]).await
+    }
+
+    /// Market with the given CLOB condition ID, if Gamma lists it
+    pub async fn get_market_by_condition_id(
+        &self,
+        condition_id: &str,
+    ) -> Result<Option<GammaMarket>> {
+        let params = GammaMarketParams {
+            condition_ids: vec![condition_id.to_string()],
+            ..Default::default()
+        };
+        self.first_market(params).await
+    }
+
+    /// Market with an outcome that trades as `token_id`, if Gamma lists it
+    pub async fn get_market_by_token_id(&self, token_id: &str) -> Result<Option<GammaMarket>> {
+        let params = GammaMarketParams {
+            clob_token_ids: vec![token_id.to_string()],
+            ..Default::default()
+        };
+        self.first_market(params).await
+    }
+
+    async fn first_market(&self, params: GammaMarketParams) -> Result<Option<GammaMarket>> {
+        let mut query = params.to_query_params();
+        query.push(("limit", "1".to_string()));
+        let markets: Vec<GammaMarket> = self.client.get_json("/markets", &query).await?;
+        Ok(markets.into_iter().next())
+    }
+
+    /// CLOB view of a Gamma market: tokens, tick size, fees and rewards
+    ///
+    /// Goes through `clob` so the market cache it shares is filled, after
+    /// which orders on the market sign without further lookups.
+    pub async fn clob_market(&self, clob: &ClobClient, market: &GammaMarket) -> Result<Market> {
+        clob.get_market(&market.condition_id).await
+    }
+
+    /// Stream events page by page, starting at `offset`
+    pub fn stream_events(
+        &self,
+        params: Option<&GammaEventParams>,
+        offset: Option<u32>,
+    ) -> Paginator<'_, GammaEvent> {
+        let params = params.cloned().unwrap_or_default();
+        self.paginate("/events", params.to_query_params(), params.limit, offset)
+    }
+
+    /// Every event matching `params`, with its markets
+    pub async fn get_events(&self, params: Option<&GammaEventParams>) -> Result<Vec<GammaEvent>> {
+        self.stream_events(params, None).try_collect().await
+    }
+
+    /// Event by its Gamma ID
+    pub async fn get_event(&self, id: &str) -> Result<GammaEvent> {
+        self.client.get_json(&format!("/events/{}", id), &[]).await
+    }
+
+    /// Stream tags page by page, starting at `offset`
+    pub fn stream_tags(&self, offset: Option<u32>) -> Paginator<'_, GammaTag> {
+        self.paginate("/tags", Vec::new(), None, offset)
+    }
+
+    pub async fn get_tags(&self) -> Result<Vec<GammaTag>> {
+        self.stream_tags(None).try_collect().await
+    }
+
+    pub async fn get_tag(&self, id: &str) -> Result<GammaTag> {
+        self.client.get_json(&format!("/tags/{}", id), &[]).await
+    }
+
+    /// Stream series page by page, starting at `offset`
+    pub fn stream_series(&self, offset: Option<u32>) -> Paginator<'_, GammaSeries> {
+        self.paginate("/series", Vec::new(), None, offset)
+    }
+
+    pub async fn get_series_list(&self) -> Result<Vec<GammaSeries>> {
+        self.stream_series(None).try_collect().await
+    }
+
+    pub async fn get_series(&self, id: &str) -> Result<GammaSeries> {
+        self.client.get_json(&format!("/series/{}", id), &[]).await
+    }
+
+    /// Search events, tags and profiles by free text
+    ///
+    /// `limit_per_type` caps the results of each kind.
+    pub async fn search(
+        &self,
+        query: &str,
+        limit_per_type: Option<u32>,
+    ) -> Result<GammaSearchResults> {
+        let mut params = vec![("q", query.to_string())];
+        if let Some(limit) = limit_per_type {
+            params.push(("limit_per_type", limit.to_string()));
+        }
+        self.client.get_json("/public-search", &params).await
+    }
+}
+
+/// Gamma sends some lists as JSON-encoded strings, e.g. `"[\"Yes\", \"No\"]"`
+fn string_encoded_list<'de, D, T>(deserializer: D) -> std::result::Result<Vec<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: DeserializeOwned,
+{
+    use serde::de::Error;
+
+    match Option::<Value>::deserialize(deserializer)? {
+        None | Some(Value::Null) => Ok(Vec::new()),
+        Some(Value::String(encoded)) if encoded.is_empty() => Ok(Vec::new()),
+        Some(Value::String(encoded)) => serde_json::from_str(&encoded).map_err(D::Error::custom),
+        Some(list) => serde_json::from_value(list).map_err(D::Error::custom),
+    }
+}
+
+fn null_as_empty<'de, D, T>(deserializer: D) -> std::result::Result<Vec<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Ok(Option::<Vec<T>>::deserialize(deserializer)?.unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::{Matcher, Server};
+    use rust_decimal_macros::dec;
+
+    fn market_json(id: u32) -> String {
+        format!(
+            r#"{{"id": "{id}", "question": "Market {id}?", "conditionId": "0xc{id}",
+                "outcomes": "[\"Yes\", \"No\"]", "outcomePrices": "[\"0.62\", \"0.38\"]",
+                "clobTokenIds": "[\"{id}1\", \"{id}2\"]", "volume": "1234.5",
+                "liquidity": 250.25, "endDate": "2026-11-03T12:00:00Z",
+                "active": true, "closed": false, "enableOrderBook": true}}"#
+        )
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_markets_are_paged_by_offset_and_link_to_clob() {
+        let mut server = Server::new_async().await;
+        for (offset, ids) in [("0", vec![1, 2]), ("2", vec![3])] {
+            let body: Vec<String> = ids.into_iter().map(market_json).collect();
+            server
+                .mock("GET", "/markets")
+                .match_query(Matcher::AllOf(vec![
+                    Matcher::UrlEncoded("active".into(), "true".into()),
+                    Matcher::UrlEncoded("limit".into(), "2".into()),
+                    Matcher::UrlEncoded("offset".into(), offset.into()),
+                ]))
+                .with_body(format!("[{}]", body.join(",")))
+                .expect(1)
+                .create_async()
+                .await;
+        }
+        server
+            .mock("GET", "/markets")
+            .match_query(Matcher::UrlEncoded("clob_token_ids".into(), "32".into()))
+            .with_body(format!("[{}]", market_json(3)))
+            .create_async()
+            .await;
+
+        let clob = ClobClient::new(&server.url());
+        let gamma = GammaClient::with_host(&clob, &server.url()).unwrap();
+        assert!(gamma.transport().rate_limiter().is_none());
+
+        let params = GammaMarketParams {
+            active: Some(true),
+            limit: Some(2),
+            ..Default::default()
+        };
+        let markets = gamma.get_markets(Some(&params)).await.unwrap();
+        assert_eq!(markets.len(), 3);
+
+        let market = &markets[0];
+        assert_eq!(market.condition_id, "0xc1");
+        assert_eq!(market.volume, Some(dec!(1234.5)));
+        assert_eq!(market.liquidity, Some(dec!(250.25)));
+        assert!(market.is_tradable());
+        assert_eq!(market.token_id("no"), Some("12"));
+        assert_eq!(
+            market.outcome_tokens()[0],
+            GammaOutcome {
+                outcome: "Yes".to_string(),
+                token_id: "11".to_string(),
+                price: Some(dec!(0.62)),
+            }
+        );
+
+        let by_token = gamma.get_market_by_token_id("32").await.unwrap().unwrap();
+        assert_eq!(by_token.id, "3");
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_events_and_search() {
+        let mut server = Server::new_async().await;
+        server
+            .mock("GET", "/events/42")
+            .with_body(format!(
+                r#"{{"id": "42", "title": "Election", "volume": 1000000,
+                    "markets": [{}, {}], "tags": [{{"id": "7", "label": "Politics"}}]}}"#,
+                market_json(1),
+                market_json(2)
+            ))
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/public-search")
+            .match_query(Matcher::UrlEncoded("q".into(), "election".into()))
+            .with_body(r#"{"events": [{"id": "42", "title": "Election"}], "tags": null}"#)
+            .create_async()
+            .await;
+
+        let gamma = GammaClient::with_host(&ClobClient::new(&server.url()), &server.url()).unwrap();
+        let event = gamma.get_event("42").await.unwrap();
+        assert_eq!(event.volume, Some(dec!(1000000)));
+        assert_eq!(
+            event.condition_ids().collect::<Vec<_>>(),
+            vec!["0xc1", "0xc2"]
+        );
+        assert_eq!(
+            event.token_ids().collect::<Vec<_>>(),
+            vec!["11", "12", "21", "22"]
+        );
+        assert_eq!(event.tags[0].label, "Politics");
+
+        let results = gamma.search("election", None).await.unwrap();
+        assert_eq!(results.events[0].id, "42");
+        assert!(results.tags.is_empty());
+    }
+}
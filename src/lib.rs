@@ -97,6 +97,7 @@ pub use crate::types::{
     BatchPriceRequest,
     BatchPriceResponse,
     BookParams,
+    CancelResponse,
     ClientConfig,
     ClientResult,
     FillEvent,
@@ -112,12 +113,19 @@ pub use crate::types::{
     OrderBook,
     OrderBookSummary,
     OrderDelta,
+    OrderExpiration,
     OrderOptions,  // For fee rate support
     OrderRequest,
     OrderStatus,
     OrderSummary,
     OrderType,
+    PostOrderOptions,
+    PostOrderResponse,
+    PostOrderStatus,
     PostOrdersArgs,  // For batch order submission
+    PriceHistoryInterval,
+    PriceHistoryRange,
+    PricePoint,
     PriceResponse,
     Rewards,
     Side,
@@ -126,17 +134,21 @@ pub use crate::types::{
     SimplifiedMarketsResponse,
     SpreadResponse,
     StreamMessage,
+    TickSizeChange,
     TickSizeResponse,
     Token,
     TokenPrice,
+    Trade,
     TradeParams,
+    TradeStatus,
     WssAuth,
     WssChannelType,
     WssSubscription,
 };
 
 // Re-export client
-pub use crate::client::{ClobClient, PolyfillClient};
+pub use crate::client::{ClobClient, ClobClientBuilder, PolyfillClient};
+pub use crate::http_config::HttpProfile;
 
 // Re-export compatibility types (for easy migration from polymarket-rs-client)
 pub use crate::client::OrderArgs;
@@ -148,26 +160,58 @@ pub use crate::errors::{PolyfillError, Result};
 pub use crate::orders::SigType;
 
 // Re-export advanced components
+pub use crate::account_pool::{AccountBalance, AccountOrder, AccountPool};
 pub use crate::book::{OrderBook as OrderBookImpl, OrderBookManager};
+pub use crate::cassette::{Cassette, CassetteMode};
+pub use crate::clock_sync::{ClockSample, ClockSync};
+pub use crate::data_api::DataApiClient;
+pub use crate::dead_mans_switch::{DeadMansSwitch, DeadMansSwitchConfig};
 pub use crate::decode::Decoder;
+pub use crate::dry_run::{DryRunLog, DryRunRequest};
 pub use crate::fill::{FillEngine, FillResult};
-pub use crate::stream::{MarketStream, StreamManager, WebSocketStream};
+pub use crate::gamma::GammaClient;
+pub use crate::market_cache::{MarketCache, MarketMetadata};
+#[cfg(feature = "prometheus")]
+pub use crate::metrics::PrometheusExporter;
+pub use crate::metrics::{ClientMetrics, MetricsSnapshot};
+#[cfg(feature = "mock-server")]
+pub use crate::mock_server::{MockClob, MockMarket};
+pub use crate::order_registry::{OrderEvent, OrderRegistry, TrackedOrder};
+pub use crate::pagination::{Page, Paginator};
+pub use crate::presign::{
+    PresignConfig, PresignKey, PresignedOrder, PresignedOrderPool, RefillReport,
+};
+pub use crate::stream::{MarketStream, StreamHeartbeat, StreamManager, WebSocketStream};
 
 // Re-export utilities
 pub use crate::utils::{crypto, math, rate_limit, retry, time, url};
 
 // Module declarations
+pub mod account_pool;
 pub mod auth;
 pub mod book;
 pub mod buffer_pool;
+pub mod cassette;
 pub mod client;
+pub mod clock_sync;
 pub mod connection_manager;
+pub mod data_api;
+pub mod dead_mans_switch;
 pub mod decode;
 pub mod dns_cache;
+pub mod dry_run;
 pub mod errors;
 pub mod fill;
+pub mod gamma;
 pub mod http_config;
+pub mod market_cache;
+pub mod metrics;
+#[cfg(feature = "mock-server")]
+pub mod mock_server;
+pub mod order_registry;
 pub mod orders;
+pub mod pagination;
+pub mod presign;
 pub mod stream;
 pub mod types;
 pub mod utils;
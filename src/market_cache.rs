@@ -0,0 +1,248 @@
+//! TTL cache for per-token market metadata
+//!
+//! Order creation needs a token's tick size, neg-risk flag and fee rate.
+//! Fetching them on every order costs two or three round trips, so the client
+//! keeps them here. The cache can be filled up front from `Market` listings
+//! and is kept honest by `tick_size_change` events from the market stream.
+
+use crate::types::{Market, OrderOptions, StreamMessage, TickSizeChange};
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use std::sync::RwLock;
+use std::time::{Duration, Instant};
+
+/// Cached metadata for a single token
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MarketMetadata {
+    pub tick_size: Option<Decimal>,
+    pub neg_risk: Option<bool>,
+    pub fee_rate_bps: Option<u32>,
+    pub min_order_size: Option<Decimal>,
+    pub accepting_orders: Option<bool>,
+    /// Condition ID of the market the token trades in
+    pub condition_id: Option<String>,
+}
+
+impl MarketMetadata {
+    /// Overwrite every field that is set in `other`
+    fn merge(&mut self, other: MarketMetadata) {
+        self.tick_size = other.tick_size.or(self.tick_size);
+        self.neg_risk = other.neg_risk.or(self.neg_risk);
+        self.fee_rate_bps = other.fee_rate_bps.or(self.fee_rate_bps);
+        self.min_order_size = other.min_order_size.or(self.min_order_size);
+        self.accepting_orders = other.accepting_orders.or(self.accepting_orders);
+        self.condition_id = other.condition_id.or(self.condition_id.take());
+    }
+
+    /// Order options filled from this metadata, if it has everything orders need
+    pub fn order_options(&self) -> Option<OrderOptions> {
+        Some(OrderOptions {
+            tick_size: Some(self.tick_size?),
+            neg_risk: Some(self.neg_risk?),
+            fee_rate_bps: self.fee_rate_bps,
+        })
+    }
+}
+
+/// Cache entry with TTL
+#[derive(Debug, Clone)]
+struct MarketCacheEntry {
+    metadata: MarketMetadata,
+    expires_at: Instant,
+}
+
+/// Token metadata cache with a fixed TTL
+///
+/// Any write refreshes the whole entry's TTL.
+#[derive(Debug)]
+pub struct MarketCache {
+    entries: RwLock<HashMap<String, MarketCacheEntry>>,
+    ttl: Duration,
+}
+
+impl Default for MarketCache {
+    fn default() -> Self {
+        Self::new(Duration::from_secs(300)) // 5 minutes default TTL
+    }
+}
+
+impl MarketCache {
+    /// Create a cache whose entries expire after `ttl`
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            entries: RwLock::new(HashMap::new()),
+            ttl,
+        }
+    }
+
+    /// Get unexpired metadata for a token
+    pub fn get(&self, token_id: &str) -> Option<MarketMetadata> {
+        let entries = self.entries.read().unwrap();
+        entries
+            .get(token_id)
+            .filter(|entry| entry.expires_at > Instant::now())
+            .map(|entry| entry.metadata.clone())
+    }
+
+    /// Merge metadata into a token's entry and refresh its TTL
+    ///
+    /// Fields left as `None` keep their cached value.
+    pub fn insert(&self, token_id: &str, metadata: MarketMetadata) {
+        let now = Instant::now();
+        let mut entries = self.entries.write().unwrap();
+        let entry = entries
+            .entry(token_id.to_string())
+            .or_insert_with(|| MarketCacheEntry {
+                metadata: MarketMetadata::default(),
+                expires_at: now,
+            });
+
+        if entry.expires_at <= now {
+            entry.metadata = MarketMetadata::default();
+        }
+        entry.metadata.merge(metadata);
+        entry.expires_at = now + self.ttl;
+    }
+
+    pub fn set_tick_size(&self, token_id: &str, tick_size: Decimal) {
+        self.insert(
+            token_id,
+            MarketMetadata {
+                tick_size: Some(tick_size),
+                ..Default::default()
+            },
+        );
+    }
+
+    pub fn set_neg_risk(&self, token_id: &str, neg_risk: bool) {
+        self.insert(
+            token_id,
+            MarketMetadata {
+                neg_risk: Some(neg_risk),
+                ..Default::default()
+            },
+        );
+    }
+
+    pub fn set_fee_rate_bps(&self, token_id: &str, fee_rate_bps: u32) {
+        self.insert(
+            token_id,
+            MarketMetadata {
+                fee_rate_bps: Some(fee_rate_bps),
+                ..Default::default()
+            },
+        );
+    }
+
+    /// Cache metadata for both tokens of a market
+    pub fn populate_from_market(&self, market: &Market) {
+        let metadata = MarketMetadata {
+            tick_size: Some(market.minimum_tick_size),
+            neg_risk: Some(market.neg_risk),
+            fee_rate_bps: market.taker_base_fee.try_into().ok(),
+            min_order_size: Some(market.minimum_order_size),
+            accepting_orders: Some(market.accepting_orders),
+            condition_id: Some(market.condition_id.clone()),
+        };
+
+        for token in &market.tokens {
+            self.insert(&token.token_id, metadata.clone());
+        }
+    }
+
+    /// Cache metadata for every token in a market listing
+    pub fn populate_from_markets<'a>(&self, markets: impl IntoIterator<Item = &'a Market>) {
+        for market in markets {
+            self.populate_from_market(market);
+        }
+    }
+
+    /// Apply a tick size change published on the market stream
+    pub fn apply_tick_size_change(&self, change: &TickSizeChange) {
+        self.set_tick_size(&change.asset_id, change.new_tick_size);
+    }
+
+    /// Update the cache from a stream message, ignoring unrelated messages
+    pub fn handle_stream_message(&self, message: &StreamMessage) {
+        if let StreamMessage::TickSizeChange { data } = message {
+            self.apply_tick_size_change(data);
+        }
+    }
+
+    /// Drop a token's entry
+    pub fn invalidate(&self, token_id: &str) {
+        self.entries.write().unwrap().remove(token_id);
+    }
+
+    /// Clear the cache
+    pub fn clear(&self) {
+        self.entries.write().unwrap().clear();
+    }
+
+    /// Number of entries, including expired ones not yet overwritten
+    pub fn len(&self) -> usize {
+        self.entries.read().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn tick(value: &str) -> Decimal {
+        Decimal::from_str(value).unwrap()
+    }
+
+    #[test]
+    fn test_insert_merges_fields() {
+        let cache = MarketCache::default();
+        cache.set_tick_size("123", tick("0.01"));
+        cache.set_neg_risk("123", true);
+
+        let metadata = cache.get("123").unwrap();
+        assert_eq!(metadata.tick_size, Some(tick("0.01")));
+        assert_eq!(metadata.neg_risk, Some(true));
+
+        let options = metadata.order_options().unwrap();
+        assert_eq!(options.tick_size, Some(tick("0.01")));
+        assert_eq!(options.fee_rate_bps, None);
+    }
+
+    #[test]
+    fn test_entries_expire() {
+        let cache = MarketCache::new(Duration::from_millis(10));
+        cache.set_tick_size("123", tick("0.01"));
+        std::thread::sleep(Duration::from_millis(20));
+
+        assert!(cache.get("123").is_none());
+
+        // A write after expiry must not resurrect stale fields
+        cache.set_neg_risk("123", false);
+        assert_eq!(cache.get("123").unwrap().tick_size, None);
+    }
+
+    #[test]
+    fn test_tick_size_change_event_updates_cache() {
+        let cache = MarketCache::default();
+        cache.set_tick_size("123", tick("0.01"));
+        cache.set_neg_risk("123", false);
+
+        cache.handle_stream_message(&StreamMessage::TickSizeChange {
+            data: TickSizeChange {
+                asset_id: "123".to_string(),
+                market: "0xm".to_string(),
+                old_tick_size: tick("0.01"),
+                new_tick_size: tick("0.001"),
+            },
+        });
+
+        let metadata = cache.get("123").unwrap();
+        assert_eq!(metadata.tick_size, Some(tick("0.001")));
+        assert_eq!(metadata.neg_risk, Some(false));
+    }
+}
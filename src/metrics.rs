@@ -0,0 +1,667 @@
+//! Request, stream and keep-alive metrics
+//!
+//! Every request a [`ClobClient`](crate::ClobClient) sends is recorded in its
+//! [`ClientMetrics`]: a latency histogram, status-code counts and byte counts
+//! per endpoint. Streams wired up with
+//! [`ClobClient::attach_stream`](crate::ClobClient::attach_stream) report
+//! their statistics as messages arrive; others can be pushed in with
+//! [`ClientMetrics::record_stream`]. Keep-alive ping counts come from the
+//! client's connection manager. [`ClobClient::metrics_snapshot`] gathers all
+//! of it into a [`MetricsSnapshot`].
+//!
+//! With the `prometheus` feature a snapshot renders to the Prometheus text
+//! format, and [`PrometheusExporter`] serves it on a local port for scraping.
+//!
+//! [`ClobClient::metrics_snapshot`]: crate::ClobClient::metrics_snapshot
+
+use crate::stream::StreamStats;
+use crate::types::Metrics;
+use chrono::{DateTime, Utc};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Upper bounds of the latency histogram buckets, in milliseconds
+pub const LATENCY_BUCKETS_MS: [f64; 12] = [
+    1.0, 2.5, 5.0, 10.0, 25.0, 50.0, 100.0, 250.0, 500.0, 1000.0, 2500.0, 10000.0,
+];
+
+/// Distribution of request latencies
+#[derive(Debug, Clone, PartialEq)]
+pub struct LatencyHistogram {
+    /// Observations per bucket of [`LATENCY_BUCKETS_MS`], plus one for slower ones
+    pub counts: Vec<u64>,
+    pub count: u64,
+    pub sum_ms: f64,
+    pub max_ms: f64,
+}
+
+impl Default for LatencyHistogram {
+    fn default() -> Self {
+        Self {
+            counts: vec![0; LATENCY_BUCKETS_MS.len() + 1],
+            count: 0,
+            sum_ms: 0.0,
+            max_ms: 0.0,
+        }
+    }
+}
+
+impl LatencyHistogram {
+    pub fn observe(&mut self, latency: Duration) {
+        let ms = latency.as_secs_f64() * 1000.0;
+        let bucket = LATENCY_BUCKETS_MS
+            .iter()
+            .position(|&bound| ms <= bound)
+            .unwrap_or(LATENCY_BUCKETS_MS.len());
+        self.counts[bucket] += 1;
+        self.count += 1;
+        self.sum_ms += ms;
+        self.max_ms = self.max_ms.max(ms);
+    }
+
+    pub fn mean_ms(&self) -> Option<f64> {
+        (self.count > 0).then(|| self.sum_ms / self.count as f64)
+    }
+
+    /// Upper bound of the bucket holding the `q` quantile, in milliseconds
+    ///
+    /// Observations slower than the last bucket report the maximum seen.
+    pub fn quantile_ms(&self, q: f64) -> Option<f64> {
+        if self.count == 0 {
+            return None;
+        }
+        let rank = (q.clamp(0.0, 1.0) * self.count as f64).ceil().max(1.0) as u64;
+        let mut seen = 0;
+        for (bucket, count) in self.counts.iter().enumerate() {
+            seen += count;
+            if seen >= rank {
+                return Some(
+                    LATENCY_BUCKETS_MS
+                        .get(bucket)
+                        .copied()
+                        .unwrap_or(self.max_ms),
+                );
+            }
+        }
+        Some(self.max_ms)
+    }
+}
+
+/// Counters for one endpoint, e.g. `GET /book`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EndpointMetrics {
+    pub requests: u64,
+    /// Requests that got a response, by status code
+    pub status_codes: BTreeMap<u16, u64>,
+    /// Requests that failed without a response
+    pub transport_errors: u64,
+    pub bytes_sent: u64,
+    /// Response body bytes read, after any decompression
+    pub bytes_received: u64,
+    pub latency: LatencyHistogram,
+}
+
+impl EndpointMetrics {
+    /// Requests that failed or got a non-2xx status
+    pub fn errors(&self) -> u64 {
+        let failed_statuses: u64 = self
+            .status_codes
+            .iter()
+            .filter(|(status, _)| !(200..300).contains(*status))
+            .map(|(_, count)| count)
+            .sum();
+        self.transport_errors + failed_statuses
+    }
+}
+
+/// Keep-alive ping counts of a client's connection manager
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct KeepaliveMetrics {
+    pub pings: u64,
+    pub failures: u64,
+    /// Unix millis of the last ping the server answered
+    pub last_success_millis: Option<u64>,
+}
+
+/// Everything a client has recorded, at one point in time
+#[derive(Debug, Clone)]
+pub struct MetricsSnapshot {
+    /// Per endpoint, keyed by method and path with IDs replaced by `{id}`
+    pub endpoints: BTreeMap<String, EndpointMetrics>,
+    /// Latest statistics of each stream, by name
+    pub streams: BTreeMap<String, StreamStats>,
+    pub keepalive: Option<KeepaliveMetrics>,
+    /// Time since the metrics were created
+    pub uptime: Duration,
+    pub taken_at: DateTime<Utc>,
+}
+
+impl MetricsSnapshot {
+    /// Roll the snapshot up into the coarse [`Metrics`] summary
+    ///
+    /// `orders_per_second` counts orders posted since the metrics were
+    /// created, and `uptime_pct` is the share of keep-alive pings answered
+    /// (100 when no ping has been sent).
+    pub fn summary(&self) -> Metrics {
+        let requests: u64 = self.endpoints.values().map(|e| e.requests).sum();
+        let errors: u64 = self.endpoints.values().map(EndpointMetrics::errors).sum();
+        let latency_ms: f64 = self.endpoints.values().map(|e| e.latency.sum_ms).sum();
+        let orders = self.endpoints.get("POST /order").map_or(0, |e| e.requests)
+            + self.endpoints.get("POST /orders").map_or(0, |e| e.requests);
+
+        let ratio = |part: u64, whole: u64| {
+            if whole == 0 {
+                0.0
+            } else {
+                part as f64 / whole as f64
+            }
+        };
+        let uptime_pct = match &self.keepalive {
+            Some(keepalive) if keepalive.pings > 0 => {
+                100.0 * ratio(keepalive.pings - keepalive.failures, keepalive.pings)
+            },
+            _ => 100.0,
+        };
+        let uptime_secs = self.uptime.as_secs_f64();
+
+        Metrics {
+            orders_per_second: if uptime_secs > 0.0 {
+                orders as f64 / uptime_secs
+            } else {
+                0.0
+            },
+            avg_latency_ms: if requests > 0 {
+                latency_ms / requests as f64
+            } else {
+                0.0
+            },
+            error_rate: ratio(errors, requests),
+            uptime_pct,
+        }
+    }
+}
+
+/// Recorder for the requests and streams of one client
+#[derive(Debug)]
+pub struct ClientMetrics {
+    started: Instant,
+    endpoints: Mutex<HashMap<String, EndpointMetrics>>,
+    streams: Mutex<HashMap<String, StreamStats>>,
+}
+
+impl Default for ClientMetrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ClientMetrics {
+    pub fn new() -> Self {
+        Self {
+            started: Instant::now(),
+            endpoints: Mutex::new(HashMap::new()),
+            streams: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Record one request
+    ///
+    /// `status` is the response status, or `None` if the request failed
+    /// before a response arrived. Response bytes are counted separately with
+    /// [`ClientMetrics::record_bytes_received`] as the body is read.
+    pub fn record_request(
+        &self,
+        method: &str,
+        path: &str,
+        status: Option<u16>,
+        latency: Duration,
+        bytes_sent: u64,
+    ) {
+        let mut endpoints = self.endpoints.lock().unwrap();
+        let endpoint = endpoints.entry(endpoint_key(method, path)).or_default();
+
+        endpoint.requests += 1;
+        endpoint.bytes_sent += bytes_sent;
+        endpoint.latency.observe(latency);
+        match status {
+            Some(status) => *endpoint.status_codes.entry(status).or_default() += 1,
+            None => endpoint.transport_errors += 1,
+        }
+    }
+
+    /// Count response body bytes read for an endpoint
+    pub fn record_bytes_received(&self, method: &str, path: &str, bytes: u64) {
+        let mut endpoints = self.endpoints.lock().unwrap();
+        endpoints
+            .entry(endpoint_key(method, path))
+            .or_default()
+            .bytes_received += bytes;
+    }
+
+    /// Store the latest statistics of the stream called `name`
+    ///
+    /// Attached streams call this on every message; call it yourself with
+    /// `MarketStream::get_stats` for streams that report nowhere else.
+    pub fn record_stream(&self, name: &str, stats: &StreamStats) {
+        let mut streams = self.streams.lock().unwrap();
+        match streams.get_mut(name) {
+            Some(latest) => *latest = stats.clone(),
+            None => {
+                streams.insert(name.to_string(), stats.clone());
+            },
+        }
+    }
+
+    /// Counters for one endpoint, e.g. `("GET", "/book")`
+    pub fn endpoint(&self, method: &str, path: &str) -> Option<EndpointMetrics> {
+        let endpoints = self.endpoints.lock().unwrap();
+        endpoints.get(&endpoint_key(method, path)).cloned()
+    }
+
+    /// Copy out everything recorded so far
+    pub fn snapshot(&self, keepalive: Option<KeepaliveMetrics>) -> MetricsSnapshot {
+        MetricsSnapshot {
+            endpoints: self
+                .endpoints
+                .lock()
+                .unwrap()
+                .iter()
+                .map(|(key, metrics)| (key.clone(), metrics.clone()))
+                .collect(),
+            streams: self
+                .streams
+                .lock()
+                .unwrap()
+                .iter()
+                .map(|(name, stats)| (name.clone(), stats.clone()))
+                .collect(),
+            keepalive,
+            uptime: self.started.elapsed(),
+            taken_at: Utc::now(),
+        }
+    }
+
+    /// Forget every request and stream recorded so far
+    pub fn reset(&self) {
+        self.endpoints.lock().unwrap().clear();
+        self.streams.lock().unwrap().clear();
+    }
+}
+
+/// `METHOD /path`, with order IDs and other identifiers replaced by `{id}`
+///
+/// Keeps one series per endpoint rather than one per order looked up.
+fn endpoint_key(method: &str, path: &str) -> String {
+    let path = path
+        .split('/')
+        .map(|segment| {
+            let is_id = segment.starts_with("0x")
+                || (!segment.is_empty() && segment.bytes().all(|b| b.is_ascii_digit()));
+            if is_id {
+                "{id}"
+            } else {
+                segment
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("/");
+    format!("{} {}", method, path)
+}
+
+#[cfg(feature = "prometheus")]
+pub use self::prometheus::PrometheusExporter;
+
+#[cfg(feature = "prometheus")]
+mod prometheus {
+    use super::{EndpointMetrics, MetricsSnapshot, LATENCY_BUCKETS_MS};
+    use crate::errors::{PolyfillError, Result};
+    use crate::stream::StreamStats;
+    use std::fmt::Write;
+    use std::net::SocketAddr;
+    use std::sync::Arc;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+    use tokio::task::JoinHandle;
+
+    impl MetricsSnapshot {
+        /// Render the snapshot in the Prometheus text exposition format
+        ///
+        /// Each family's HELP and TYPE lines directly precede its samples;
+        /// families with nothing to report are left out.
+        pub fn to_prometheus(&self) -> String {
+            let mut out = String::new();
+
+            if !self.endpoints.is_empty() {
+                family(
+                    &mut out,
+                    "http_requests_total",
+                    "counter",
+                    "Requests answered, by endpoint and status",
+                );
+                for (endpoint, metrics) in &self.endpoints {
+                    for (status, count) in &metrics.status_codes {
+                        let _ = writeln!(
+                            out,
+                            "polyfill_http_requests_total{{endpoint=\"{}\",status=\"{}\"}} {}",
+                            escape(endpoint),
+                            status,
+                            count
+                        );
+                    }
+                }
+
+                let counters: [Counter<EndpointMetrics>; 3] = [
+                    (
+                        "http_transport_errors_total",
+                        "Requests that got no response",
+                        |metrics| metrics.transport_errors,
+                    ),
+                    (
+                        "http_request_bytes_total",
+                        "Request body bytes sent",
+                        |metrics| metrics.bytes_sent,
+                    ),
+                    (
+                        "http_response_bytes_total",
+                        "Response body bytes received",
+                        |metrics| metrics.bytes_received,
+                    ),
+                ];
+                for (name, help, value) in counters {
+                    family(&mut out, name, "counter", help);
+                    for (endpoint, metrics) in &self.endpoints {
+                        let _ = writeln!(
+                            out,
+                            "polyfill_{}{{endpoint=\"{}\"}} {}",
+                            name,
+                            escape(endpoint),
+                            value(metrics)
+                        );
+                    }
+                }
+
+                family(
+                    &mut out,
+                    "http_request_duration_seconds",
+                    "histogram",
+                    "Request latency",
+                );
+                for (endpoint, metrics) in &self.endpoints {
+                    let label = format!("endpoint=\"{}\"", escape(endpoint));
+                    let mut cumulative = 0;
+                    for (bound, count) in LATENCY_BUCKETS_MS.iter().zip(&metrics.latency.counts) {
+                        cumulative += count;
+                        let _ = writeln!(
+                            out,
+                            "polyfill_http_request_duration_seconds_bucket{{{},le=\"{}\"}} {}",
+                            label,
+                            bound / 1000.0,
+                            cumulative
+                        );
+                    }
+                    let _ = writeln!(
+                        out,
+                        "polyfill_http_request_duration_seconds_bucket{{{},le=\"+Inf\"}} {}",
+                        label, metrics.latency.count
+                    );
+                    let _ = writeln!(
+                        out,
+                        "polyfill_http_request_duration_seconds_sum{{{}}} {}",
+                        label,
+                        metrics.latency.sum_ms / 1000.0
+                    );
+                    let _ = writeln!(
+                        out,
+                        "polyfill_http_request_duration_seconds_count{{{}}} {}",
+                        label, metrics.latency.count
+                    );
+                }
+            }
+
+            if !self.streams.is_empty() {
+                let counters: [Counter<StreamStats>; 4] = [
+                    (
+                        "stream_messages_received_total",
+                        "Stream messages received",
+                        |stats| stats.messages_received,
+                    ),
+                    (
+                        "stream_messages_sent_total",
+                        "Stream messages sent",
+                        |stats| stats.messages_sent,
+                    ),
+                    ("stream_errors_total", "Stream errors", |stats| stats.errors),
+                    ("stream_reconnects_total", "Stream reconnections", |stats| {
+                        stats.reconnect_count as u64
+                    }),
+                ];
+                for (name, help, value) in counters {
+                    family(&mut out, name, "counter", help);
+                    for (stream, stats) in &self.streams {
+                        let _ = writeln!(
+                            out,
+                            "polyfill_{}{{stream=\"{}\"}} {}",
+                            name,
+                            escape(stream),
+                            value(stats)
+                        );
+                    }
+                }
+            }
+
+            if let Some(keepalive) = &self.keepalive {
+                family(
+                    &mut out,
+                    "keepalive_pings_total",
+                    "counter",
+                    "Keep-alive pings sent",
+                );
+                let _ = writeln!(out, "polyfill_keepalive_pings_total {}", keepalive.pings);
+                family(
+                    &mut out,
+                    "keepalive_failures_total",
+                    "counter",
+                    "Keep-alive pings that failed",
+                );
+                let _ = writeln!(
+                    out,
+                    "polyfill_keepalive_failures_total {}",
+                    keepalive.failures
+                );
+            }
+
+            family(
+                &mut out,
+                "uptime_seconds",
+                "gauge",
+                "Time since the metrics were created",
+            );
+            let _ = writeln!(out, "polyfill_uptime_seconds {}", self.uptime.as_secs_f64());
+            out
+        }
+    }
+
+    /// A counter family: its name, help text and how to read it from a source
+    type Counter<T> = (&'static str, &'static str, fn(&T) -> u64);
+
+    /// Write the HELP and TYPE lines that open a metric family
+    fn family(out: &mut String, name: &str, kind: &str, help: &str) {
+        let _ = writeln!(out, "# HELP polyfill_{} {}", name, help);
+        let _ = writeln!(out, "# TYPE polyfill_{} {}", name, kind);
+    }
+
+    /// Escape a label value for the text format
+    fn escape(value: &str) -> String {
+        value
+            .replace('\\', "\\\\")
+            .replace('"', "\\\"")
+            .replace('\n', "\\n")
+    }
+
+    /// Serves metrics snapshots to Prometheus scrapers over plain HTTP
+    ///
+    /// Every request, whatever its path, gets the current snapshot. The
+    /// server stops when this is dropped.
+    #[derive(Debug)]
+    pub struct PrometheusExporter {
+        address: SocketAddr,
+        server: JoinHandle<()>,
+    }
+
+    impl PrometheusExporter {
+        /// Listen on `address` and answer each scrape with `snapshot()`
+        ///
+        /// Bind port 0 to pick a free port; see [`PrometheusExporter::local_addr`].
+        pub async fn start<F>(address: SocketAddr, snapshot: F) -> Result<Self>
+        where
+            F: Fn() -> MetricsSnapshot + Send + Sync + 'static,
+        {
+            let listener = TcpListener::bind(address)
+                .await
+                .map_err(|e| PolyfillError::network("Failed to bind metrics exporter", e))?;
+            let address = listener
+                .local_addr()
+                .map_err(|e| PolyfillError::network("Failed to bind metrics exporter", e))?;
+
+            let snapshot = Arc::new(snapshot);
+            let server = tokio::spawn(async move {
+                while let Ok((mut stream, _)) = listener.accept().await {
+                    let snapshot = Arc::clone(&snapshot);
+                    tokio::spawn(async move {
+                        // The request itself does not matter; read its head and answer
+                        let mut request = [0u8; 1024];
+                        let _ = stream.read(&mut request).await;
+
+                        let body = snapshot().to_prometheus();
+                        let response = format!(
+                            "HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                            body.len(),
+                            body
+                        );
+                        let _ = stream.write_all(response.as_bytes()).await;
+                        let _ = stream.shutdown().await;
+                    });
+                }
+            });
+
+            Ok(Self { address, server })
+        }
+
+        /// Address the exporter is listening on
+        pub fn local_addr(&self) -> SocketAddr {
+            self.address
+        }
+    }
+
+    impl Drop for PrometheusExporter {
+        fn drop(&mut self) {
+            self.server.abort();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_records_requests_per_endpoint() {
+        let metrics = ClientMetrics::new();
+        metrics.record_request("GET", "/book", Some(200), Duration::from_millis(3), 0);
+        metrics.record_bytes_received("GET", "/book", 120);
+        metrics.record_request("GET", "/book", Some(429), Duration::from_millis(40), 0);
+        metrics.record_bytes_received("GET", "/book", 20);
+        metrics.record_request("POST", "/order", None, Duration::from_secs(20), 512);
+        metrics.record_request(
+            "GET",
+            "/data/order/0xabc",
+            Some(200),
+            Duration::from_millis(1),
+            0,
+        );
+
+        let book = metrics.endpoint("GET", "/book").unwrap();
+        assert_eq!(book.requests, 2);
+        assert_eq!(book.status_codes[&429], 1);
+        assert_eq!(book.bytes_received, 140);
+        assert_eq!(book.errors(), 1);
+        assert_eq!(book.latency.quantile_ms(0.5), Some(5.0));
+        assert_eq!(book.latency.quantile_ms(1.0), Some(50.0));
+
+        let snapshot = metrics.snapshot(Some(KeepaliveMetrics {
+            pings: 4,
+            failures: 1,
+            last_success_millis: None,
+        }));
+        assert!(snapshot.endpoints.contains_key("GET /data/order/{id}"));
+        let order = &snapshot.endpoints["POST /order"];
+        assert_eq!(order.transport_errors, 1);
+        assert_eq!(order.latency.quantile_ms(0.99), Some(20_000.0));
+
+        let summary = snapshot.summary();
+        assert_eq!(summary.error_rate, 0.5);
+        assert_eq!(summary.uptime_pct, 75.0);
+        assert!(summary.avg_latency_ms > 5000.0);
+    }
+
+    #[cfg(feature = "prometheus")]
+    #[tokio::test]
+    async fn test_prometheus_exporter_serves_snapshot() {
+        let metrics = std::sync::Arc::new(ClientMetrics::new());
+        metrics.record_request("GET", "/book", Some(200), Duration::from_millis(2), 0);
+        metrics.record_bytes_received("GET", "/book", 10);
+
+        metrics.record_request("POST", "/order", Some(400), Duration::from_millis(5), 100);
+
+        let text = metrics.snapshot(None).to_prometheus();
+        assert!(
+            text.contains("polyfill_http_requests_total{endpoint=\"GET /book\",status=\"200\"} 1")
+        );
+        assert!(text.contains(
+            "polyfill_http_request_duration_seconds_bucket{endpoint=\"GET /book\",le=\"0.0025\"} 1"
+        ));
+
+        // Every sample follows its own family's header, with each family
+        // declared once and appearing as a single block
+        let mut seen = Vec::new();
+        let mut current: Option<&str> = None;
+        for line in text.lines() {
+            if let Some(rest) = line.strip_prefix("# TYPE ") {
+                let name = rest.split(' ').next().unwrap();
+                assert!(!seen.contains(&name), "{} declared twice", name);
+                seen.push(name);
+                current = Some(name);
+            } else if !line.starts_with('#') {
+                let sample = line.split(['{', ' ']).next().unwrap();
+                let family = current.expect("sample before any family");
+                assert!(
+                    sample == family
+                        || sample.strip_prefix(family).is_some_and(|suffix| {
+                            ["_bucket", "_sum", "_count"].contains(&suffix)
+                        }),
+                    "{} listed under {}",
+                    sample,
+                    family
+                );
+            }
+        }
+        // Nothing was recorded for streams or the keep-alive connection
+        assert!(!text.contains("polyfill_stream_"));
+        assert!(!text.contains("polyfill_keepalive_"));
+
+        let source = std::sync::Arc::clone(&metrics);
+        let exporter = PrometheusExporter::start("127.0.0.1:0".parse().unwrap(), move || {
+            source.snapshot(None)
+        })
+        .await
+        .unwrap();
+        let scraped = reqwest::get(format!("http://{}/metrics", exporter.local_addr()))
+            .await
+            .unwrap()
+            .text()
+            .await
+            .unwrap();
+        assert!(scraped.contains("polyfill_http_response_bytes_total{endpoint=\"GET /book\"} 10"));
+    }
+}
@@ -0,0 +1,1011 @@
+//! In-process mock of the CLOB REST API for offline end-to-end tests
+//!
+//! [`MockClob`] serves the market data, order, cancel and query endpoints from
+//! an in-memory matching book on a local port, so a real
+//! [`ClobClient`](crate::ClobClient) can trade against it without internet
+//! access. Authentication is checked the way the exchange checks it: L1
+//! headers must carry a valid EIP-712 `ClobAuth` signature, L2 headers a valid
+//! HMAC over the exact request body, and every posted order a valid EIP-712
+//! signature from the API key's address.
+//!
+//! Orders match with price-time priority at the resting order's price.
+//! Balances, allowances and settlement are not modelled.
+//!
+//! Requires the `mock-server` feature.
+
+use crate::auth::{recover_clob_auth_signer, verify_hmac_signature};
+use crate::errors::{PolyfillError, Result};
+use crate::orders::signed_order_hash;
+use crate::pagination::END_CURSOR;
+use crate::types::{
+    ApiCredentials, CancelResponse, MakerOrder, OpenOrder, OrderType, PostOrder, PostOrderResponse,
+    PostOrderStatus, Side, SignedOrderRequest, Trade, TradeStatus, TraderSide,
+    GTD_SECURITY_THRESHOLD,
+};
+use crate::utils::{math, time};
+use alloy_primitives::{hex, Address, PrimitiveSignature, B256, U256};
+use base64::engine::general_purpose::URL_SAFE;
+use base64::Engine;
+use http_body_util::{BodyExt, Full};
+use hyper::body::{Bytes, Incoming};
+use hyper::server::conn::http1;
+use hyper::service::service_fn;
+use hyper::{Method, Request, Response, StatusCode};
+use hyper_util::rt::TokioIo;
+use rand::Rng;
+use rust_decimal::Decimal;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::convert::Infallible;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use tokio::net::TcpListener;
+use tokio::task::JoinHandle;
+
+/// Status of an order resting on the book
+const LIVE: &str = "LIVE";
+/// Status of an order filled in full
+const MATCHED: &str = "MATCHED";
+/// Status of an order canceled, expired or killed before it filled
+const CANCELED: &str = "CANCELED";
+
+/// A market traded by the mock exchange
+#[derive(Debug, Clone, PartialEq)]
+pub struct MockMarket {
+    pub condition_id: String,
+    pub token_id: String,
+    pub tick_size: Decimal,
+    pub neg_risk: bool,
+    pub outcome: String,
+}
+
+impl MockMarket {
+    /// A "Yes" outcome token with a 0.01 tick
+    pub fn new(condition_id: impl Into<String>, token_id: impl Into<String>) -> Self {
+        Self {
+            condition_id: condition_id.into(),
+            token_id: token_id.into(),
+            tick_size: Decimal::new(1, 2),
+            neg_risk: false,
+            outcome: "Yes".to_string(),
+        }
+    }
+
+    pub fn with_tick_size(mut self, tick_size: Decimal) -> Self {
+        self.tick_size = tick_size;
+        self
+    }
+
+    pub fn with_neg_risk(mut self, neg_risk: bool) -> Self {
+        self.neg_risk = neg_risk;
+        self
+    }
+}
+
+/// A CLOB exchange served from a local port
+///
+/// The server stops when this is dropped.
+#[derive(Debug)]
+pub struct MockClob {
+    url: String,
+    exchange: Arc<Mutex<Exchange>>,
+    server: JoinHandle<()>,
+}
+
+impl MockClob {
+    /// Start an exchange that verifies orders signed for Polygon mainnet
+    pub async fn start() -> Result<Self> {
+        Self::start_with_chain_id(137).await
+    }
+
+    /// Start an exchange that verifies orders signed for `chain_id`
+    pub async fn start_with_chain_id(chain_id: u64) -> Result<Self> {
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .await
+            .map_err(|e| PolyfillError::network("Failed to bind mock exchange", e))?;
+        let address = listener
+            .local_addr()
+            .map_err(|e| PolyfillError::network("Failed to bind mock exchange", e))?;
+
+        let exchange = Arc::new(Mutex::new(Exchange::new(chain_id)));
+        let shared = Arc::clone(&exchange);
+        let server = tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let exchange = Arc::clone(&shared);
+                tokio::spawn(async move {
+                    let service = service_fn(move |request| serve(Arc::clone(&exchange), request));
+                    let _ = http1::Builder::new()
+                        .serve_connection(TokioIo::new(stream), service)
+                        .await;
+                });
+            }
+        });
+
+        Ok(Self {
+            url: format!("http://{}", address),
+            exchange,
+            server,
+        })
+    }
+
+    /// Base URL to hand to a client
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// List a market's token for trading
+    pub fn add_market(&self, market: MockMarket) {
+        let mut exchange = self.exchange.lock().unwrap();
+        exchange.markets.insert(market.token_id.clone(), market);
+    }
+
+    /// Register API credentials for `address`
+    ///
+    /// They are also returned by `GET /auth/derive-api-key` with nonce 0.
+    pub fn add_api_key(&self, address: Address, credentials: ApiCredentials) {
+        self.exchange
+            .lock()
+            .unwrap()
+            .register(address, U256::ZERO, credentials);
+    }
+
+    /// Every order resting on the book, in placement order
+    pub fn open_orders(&self) -> Vec<OpenOrder> {
+        let exchange = self.exchange.lock().unwrap();
+        let mut orders: Vec<_> = exchange
+            .orders
+            .values()
+            .filter(|entry| entry.order.status == LIVE)
+            .collect();
+        orders.sort_by_key(|entry| entry.sequence);
+        orders
+            .into_iter()
+            .map(|entry| entry.order.clone())
+            .collect()
+    }
+
+    /// An order in any state, by ID
+    pub fn order(&self, order_id: &str) -> Option<OpenOrder> {
+        let exchange = self.exchange.lock().unwrap();
+        exchange
+            .orders
+            .get(order_id)
+            .map(|entry| entry.order.clone())
+    }
+
+    /// Every trade matched so far, oldest first
+    pub fn trades(&self) -> Vec<Trade> {
+        self.exchange.lock().unwrap().trades.clone()
+    }
+}
+
+impl Drop for MockClob {
+    fn drop(&mut self) {
+        self.server.abort();
+    }
+}
+
+async fn serve(
+    exchange: Arc<Mutex<Exchange>>,
+    request: Request<Incoming>,
+) -> std::result::Result<Response<Full<Bytes>>, Infallible> {
+    let (parts, body) = request.into_parts();
+    let body = body
+        .collect()
+        .await
+        .map(|collected| collected.to_bytes())
+        .unwrap_or_default();
+
+    let request = MockRequest {
+        method: parts.method,
+        path: parts.uri.path().to_string(),
+        query: parts
+            .uri
+            .query()
+            .map(|query| {
+                url::form_urlencoded::parse(query.as_bytes())
+                    .into_owned()
+                    .collect()
+            })
+            .unwrap_or_default(),
+        headers: parts
+            .headers
+            .iter()
+            .filter_map(|(name, value)| {
+                Some((name.as_str().to_string(), value.to_str().ok()?.to_string()))
+            })
+            .collect(),
+        body: String::from_utf8_lossy(&body).into_owned(),
+    };
+
+    let reply = exchange.lock().unwrap().handle(&request);
+    let response = Response::builder()
+        .status(reply.status)
+        .header("content-type", "application/json")
+        .body(Full::new(Bytes::from(reply.body.to_string())))
+        .expect("static response parts are valid");
+    Ok(response)
+}
+
+/// A request read off the wire in full
+struct MockRequest {
+    method: Method,
+    path: String,
+    query: HashMap<String, String>,
+    /// Header names are lowercase
+    headers: HashMap<String, String>,
+    body: String,
+}
+
+impl MockRequest {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(name).map(String::as_str)
+    }
+
+    /// A query parameter, treating an empty value as absent
+    fn query(&self, name: &str) -> Option<&str> {
+        self.query
+            .get(name)
+            .map(String::as_str)
+            .filter(|value| !value.is_empty())
+    }
+
+    fn json<T: serde::de::DeserializeOwned>(&self) -> std::result::Result<T, Reply> {
+        serde_json::from_str(&self.body).map_err(|e| {
+            Reply::error(
+                StatusCode::BAD_REQUEST,
+                format!("Invalid request body: {}", e),
+            )
+        })
+    }
+}
+
+/// Status and JSON body of a response
+struct Reply {
+    status: StatusCode,
+    body: Value,
+}
+
+type Handled = std::result::Result<Reply, Reply>;
+
+impl Reply {
+    fn ok(body: impl Serialize) -> Self {
+        Self {
+            status: StatusCode::OK,
+            body: serde_json::to_value(body).unwrap_or(Value::Null),
+        }
+    }
+
+    fn error(status: StatusCode, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            body: json!({ "error": message.into() }),
+        }
+    }
+
+    fn unauthorized() -> Self {
+        Self::error(StatusCode::UNAUTHORIZED, "Unauthorized/Invalid api key")
+    }
+}
+
+#[derive(Debug)]
+struct Account {
+    address: Address,
+    credentials: ApiCredentials,
+}
+
+#[derive(Debug)]
+struct BookEntry {
+    order: OpenOrder,
+    /// Placement order, for time priority
+    sequence: u64,
+}
+
+impl BookEntry {
+    fn remaining(&self) -> Decimal {
+        self.order.original_size - self.order.size_matched
+    }
+}
+
+/// Exchange state behind the server
+#[derive(Debug)]
+struct Exchange {
+    chain_id: u64,
+    markets: HashMap<String, MockMarket>,
+    /// Accounts by API key
+    accounts: HashMap<String, Account>,
+    /// API key issued for each address and nonce
+    api_keys: HashMap<(Address, U256), String>,
+    orders: HashMap<String, BookEntry>,
+    trades: Vec<Trade>,
+    sequence: u64,
+}
+
+impl Exchange {
+    fn new(chain_id: u64) -> Self {
+        Self {
+            chain_id,
+            markets: HashMap::new(),
+            accounts: HashMap::new(),
+            api_keys: HashMap::new(),
+            orders: HashMap::new(),
+            trades: Vec::new(),
+            sequence: 0,
+        }
+    }
+
+    fn register(&mut self, address: Address, nonce: U256, credentials: ApiCredentials) {
+        self.api_keys
+            .insert((address, nonce), credentials.api_key.clone());
+        self.accounts.insert(
+            credentials.api_key.clone(),
+            Account {
+                address,
+                credentials,
+            },
+        );
+    }
+
+    fn handle(&mut self, request: &MockRequest) -> Reply {
+        self.expire_orders(time::now_secs());
+
+        let handled = match (&request.method, request.path.as_str()) {
+            (&Method::GET, "/") | (&Method::GET, "/ok") => Ok(Reply::ok("OK")),
+            (&Method::GET, "/time") => Ok(Reply::ok(time::now_secs())),
+            (&Method::GET, "/book") => self.book(request),
+            (&Method::GET, "/price") => self.price(request),
+            (&Method::GET, "/midpoint") => self.midpoint(request),
+            (&Method::GET, "/tick-size") => self
+                .market(request)
+                .map(|market| Reply::ok(json!({ "minimum_tick_size": market.tick_size }))),
+            (&Method::GET, "/neg-risk") => self
+                .market(request)
+                .map(|market| Reply::ok(json!({ "neg_risk": market.neg_risk }))),
+            (&Method::POST, "/auth/api-key") => self.create_api_key(request),
+            (&Method::GET, "/auth/derive-api-key") => self.derive_api_key(request),
+            (&Method::POST, "/order") => self.post_order(request),
+            (&Method::POST, "/orders") => self.post_orders(request),
+            (&Method::DELETE, "/order") => self.cancel(request),
+            (&Method::DELETE, "/orders") => self.cancel_orders(request),
+            (&Method::DELETE, "/cancel-all") => self.cancel_all(request),
+            (&Method::DELETE, "/cancel-market-orders") => self.cancel_market_orders(request),
+            (&Method::GET, "/data/orders") => self.list_orders(request),
+            (&Method::GET, "/data/trades") => self.list_trades(request),
+            (&Method::GET, path) if path.starts_with("/data/order/") => {
+                self.get_order(request, &path["/data/order/".len()..])
+            },
+            _ => Err(Reply::error(StatusCode::NOT_FOUND, "Not found")),
+        };
+        handled.unwrap_or_else(|reply| reply)
+    }
+
+    // Market data
+
+    fn market(&self, request: &MockRequest) -> std::result::Result<&MockMarket, Reply> {
+        request
+            .query("token_id")
+            .and_then(|token_id| self.markets.get(token_id))
+            .ok_or_else(|| {
+                Reply::error(
+                    StatusCode::NOT_FOUND,
+                    "No orderbook exists for the requested token id",
+                )
+            })
+    }
+
+    /// Resting size per price on one side of a book, best price first
+    fn levels(&self, token_id: &str, side: Side) -> Vec<(Decimal, Decimal)> {
+        let mut levels = BTreeMap::new();
+        for entry in self.orders.values() {
+            let order = &entry.order;
+            if order.status == LIVE && order.asset_id == token_id && order.side == side {
+                *levels.entry(order.price).or_insert(Decimal::ZERO) += entry.remaining();
+            }
+        }
+
+        match side {
+            Side::BUY => levels.into_iter().rev().collect(),
+            Side::SELL => levels.into_iter().collect(),
+        }
+    }
+
+    fn book(&self, request: &MockRequest) -> Handled {
+        let market = self.market(request)?;
+        // The exchange lists each side with the best level last
+        let side = |side| {
+            self.levels(&market.token_id, side)
+                .into_iter()
+                .rev()
+                .map(
+                    |(price, size)| json!({ "price": price.to_string(), "size": size.to_string() }),
+                )
+                .collect::<Vec<_>>()
+        };
+
+        Ok(Reply::ok(json!({
+            "market": market.condition_id,
+            "asset_id": market.token_id,
+            "hash": "",
+            "timestamp": time::now_millis().to_string(),
+            "bids": side(Side::BUY),
+            "asks": side(Side::SELL),
+        })))
+    }
+
+    fn best_price(&self, token_id: &str, side: Side) -> Option<Decimal> {
+        self.levels(token_id, side).first().map(|(price, _)| *price)
+    }
+
+    fn price(&self, request: &MockRequest) -> Handled {
+        let market = self.market(request)?;
+        let side = match request.query("side") {
+            Some("BUY") => Side::BUY,
+            Some("SELL") => Side::SELL,
+            _ => return Err(Reply::error(StatusCode::BAD_REQUEST, "Invalid side")),
+        };
+
+        self.best_price(&market.token_id, side)
+            .map(|price| Reply::ok(json!({ "price": price.to_string() })))
+            .ok_or_else(|| Reply::error(StatusCode::NOT_FOUND, "No orders found"))
+    }
+
+    fn midpoint(&self, request: &MockRequest) -> Handled {
+        let market = self.market(request)?;
+        let bid = self.best_price(&market.token_id, Side::BUY);
+        let ask = self.best_price(&market.token_id, Side::SELL);
+
+        match (bid, ask) {
+            (Some(bid), Some(ask)) => Ok(Reply::ok(
+                json!({ "mid": ((bid + ask) / Decimal::TWO).to_string() }),
+            )),
+            _ => Err(Reply::error(StatusCode::NOT_FOUND, "No orders found")),
+        }
+    }
+
+    // Authentication
+
+    /// Check L1 headers, returning the signing address and nonce
+    fn authenticate_l1(
+        &self,
+        request: &MockRequest,
+    ) -> std::result::Result<(Address, U256), Reply> {
+        let invalid = || Reply::error(StatusCode::UNAUTHORIZED, "Invalid L1 Request headers");
+
+        let address = request
+            .header("poly_address")
+            .and_then(|address| Address::from_str(address).ok())
+            .ok_or_else(invalid)?;
+        let timestamp = request.header("poly_timestamp").ok_or_else(invalid)?;
+        let nonce = request
+            .header("poly_nonce")
+            .and_then(|nonce| U256::from_str_radix(nonce, 10).ok())
+            .ok_or_else(invalid)?;
+        let signature = request.header("poly_signature").ok_or_else(invalid)?;
+
+        match recover_clob_auth_signer(address, timestamp, nonce, signature) {
+            Ok(signer) if signer == address => Ok((address, nonce)),
+            _ => Err(invalid()),
+        }
+    }
+
+    /// Check L2 headers, returning the caller's API key
+    fn authenticate_l2(&self, request: &MockRequest) -> std::result::Result<String, Reply> {
+        let api_key = request
+            .header("poly_api_key")
+            .ok_or_else(Reply::unauthorized)?;
+        let account = self.accounts.get(api_key).ok_or_else(Reply::unauthorized)?;
+
+        let address = request
+            .header("poly_address")
+            .and_then(|address| Address::from_str(address).ok());
+        if address != Some(account.address)
+            || request.header("poly_passphrase") != Some(account.credentials.passphrase.as_str())
+        {
+            return Err(Reply::unauthorized());
+        }
+
+        let valid = match (
+            request.header("poly_timestamp"),
+            request.header("poly_signature"),
+        ) {
+            (Some(timestamp), Some(signature)) => verify_hmac_signature(
+                &account.credentials.secret,
+                timestamp,
+                request.method.as_str(),
+                &request.path,
+                &request.body,
+                signature,
+            ),
+            _ => false,
+        };
+        if !valid {
+            return Err(Reply::error(
+                StatusCode::UNAUTHORIZED,
+                "Invalid HMAC signature",
+            ));
+        }
+
+        Ok(api_key.to_string())
+    }
+
+    fn create_api_key(&mut self, request: &MockRequest) -> Handled {
+        let (address, nonce) = self.authenticate_l1(request)?;
+        if self.api_keys.contains_key(&(address, nonce)) {
+            return Err(Reply::error(
+                StatusCode::BAD_REQUEST,
+                "Could not create api key",
+            ));
+        }
+
+        let mut rng = rand::thread_rng();
+        let credentials = ApiCredentials {
+            api_key: uuid::Uuid::new_v4().to_string(),
+            secret: URL_SAFE.encode(rng.gen::<[u8; 32]>()),
+            passphrase: hex::encode(rng.gen::<[u8; 32]>()),
+        };
+        self.register(address, nonce, credentials.clone());
+        Ok(Reply::ok(credentials))
+    }
+
+    fn derive_api_key(&self, request: &MockRequest) -> Handled {
+        let key = self.authenticate_l1(request)?;
+        self.api_keys
+            .get(&key)
+            .and_then(|api_key| self.accounts.get(api_key))
+            .map(|account| Reply::ok(&account.credentials))
+            .ok_or_else(|| Reply::error(StatusCode::BAD_REQUEST, "Could not derive api key!"))
+    }
+
+    // Orders
+
+    /// Rejections are reported in band, inside a 200 response
+    fn post_order(&mut self, request: &MockRequest) -> Handled {
+        let api_key = self.authenticate_l2(request)?;
+        let post: PostOrder = request.json()?;
+
+        Ok(Reply::ok(self.place_or_reject(&api_key, post)))
+    }
+
+    fn post_orders(&mut self, request: &MockRequest) -> Handled {
+        let api_key = self.authenticate_l2(request)?;
+        let posts: Vec<PostOrder> = request.json()?;
+
+        let responses: Vec<PostOrderResponse> = posts
+            .into_iter()
+            .map(|post| self.place_or_reject(&api_key, post))
+            .collect();
+        Ok(Reply::ok(responses))
+    }
+
+    fn place_or_reject(&mut self, api_key: &str, post: PostOrder) -> PostOrderResponse {
+        self.place(api_key, post)
+            .unwrap_or_else(|message| PostOrderResponse {
+                success: false,
+                error_msg: message,
+                order_id: String::new(),
+                transaction_hashes: Vec::new(),
+                status: None,
+                taking_amount: None,
+                making_amount: None,
+            })
+    }
+
+    /// Validate an order, match it against the book and rest what is left
+    fn place(
+        &mut self,
+        api_key: &str,
+        post: PostOrder,
+    ) -> std::result::Result<PostOrderResponse, String> {
+        let PostOrder {
+            order,
+            owner,
+            order_type,
+            post_only,
+        } = post;
+        let account = &self.accounts[api_key];
+
+        if owner != api_key {
+            return Err("the order owner has to be the owner of the API KEY".to_string());
+        }
+        let signer = Address::from_str(&order.signer)
+            .map_err(|e| format!("invalid order payload: {}", e))?;
+        if signer != account.address {
+            return Err(
+                "the order signer address has to be the address of the API KEY".to_string(),
+            );
+        }
+        let market = self
+            .markets
+            .get(&order.token_id)
+            .cloned()
+            .ok_or_else(|| format!("invalid token id {}", order.token_id))?;
+
+        let order_id = signed_order_hash(&order, self.chain_id, market.neg_risk)
+            .map_err(|e| format!("invalid order payload: {}", e))?;
+        let digest = B256::from_str(&order_id).map_err(|e| e.to_string())?;
+        let recovered = PrimitiveSignature::from_str(&order.signature)
+            .and_then(|signature| signature.recover_address_from_prehash(&digest));
+        if recovered.ok() != Some(signer) {
+            return Err("invalid order signature".to_string());
+        }
+        if self.orders.contains_key(&order_id) {
+            return Err(format!("order {} is invalid. Duplicated.", order_id));
+        }
+
+        let (side, price, size) = order_terms(&order)?;
+        let tick = market.tick_size;
+        if price < tick || price > Decimal::ONE - tick || !(price % tick).is_zero() {
+            return Err(format!(
+                "invalid price ({}), min tick size: {}",
+                price, tick
+            ));
+        }
+        let expiration: u64 = order
+            .expiration
+            .parse()
+            .map_err(|_| "invalid order payload: expiration".to_string())?;
+        let now = time::now_secs();
+        if order_type == OrderType::GTD && expiration <= now + GTD_SECURITY_THRESHOLD.as_secs() {
+            return Err(format!(
+                "invalid expiration value ({}), current unix timestamp is {}",
+                expiration, now
+            ));
+        }
+
+        let makers = self.crossing_orders(&market.token_id, side, price);
+        let available: Decimal = makers.iter().map(|id| self.orders[id].remaining()).sum();
+        if post_only && !makers.is_empty() {
+            return Err("invalid post-only order: order crosses book".to_string());
+        }
+        if order_type == OrderType::FOK && available < size {
+            return Err(
+                "order couldn't be fully filled. FOK orders are fully filled or killed."
+                    .to_string(),
+            );
+        }
+        if order_type == OrderType::FAK && makers.is_empty() {
+            return Err("no orders found to match with FAK order. FAK orders are partially filled or killed if no match is found.".to_string());
+        }
+
+        // Fill against the book at each resting order's price
+        let trade_id = uuid::Uuid::new_v4().to_string();
+        let mut remaining = size;
+        let mut notional = Decimal::ZERO;
+        let mut maker_orders = Vec::new();
+        for maker_id in makers {
+            if remaining.is_zero() {
+                break;
+            }
+            let entry = self
+                .orders
+                .get_mut(&maker_id)
+                .expect("crossing order exists");
+            let fill = remaining.min(entry.remaining());
+            let maker = &mut entry.order;
+            maker.size_matched += fill;
+            maker.associate_trades.push(trade_id.clone());
+            if maker.size_matched == maker.original_size {
+                maker.status = MATCHED.to_string();
+            }
+
+            remaining -= fill;
+            notional += fill * maker.price;
+            maker_orders.push(MakerOrder {
+                order_id: maker.id.clone(),
+                owner: maker.owner.clone(),
+                maker_address: maker.maker_address.clone(),
+                matched_amount: fill,
+                price: maker.price,
+                fee_rate_bps: 0,
+                asset_id: maker.asset_id.clone(),
+                outcome: maker.outcome.clone(),
+                side: Some(maker.side),
+            });
+        }
+        let filled = size - remaining;
+
+        if !filled.is_zero() {
+            self.trades.push(Trade {
+                id: trade_id.clone(),
+                taker_order_id: order_id.clone(),
+                market: market.condition_id.clone(),
+                asset_id: market.token_id.clone(),
+                side,
+                size: filled,
+                fee_rate_bps: order.fee_rate_bps.parse().unwrap_or(0),
+                price: (notional / filled).normalize(),
+                status: TradeStatus::MATCHED,
+                match_time: now,
+                last_update: Some(now),
+                outcome: market.outcome.clone(),
+                bucket_index: 0,
+                owner: api_key.to_string(),
+                maker_address: order.maker.clone(),
+                maker_orders,
+                transaction_hash: None,
+                trader_side: Some(TraderSide::TAKER),
+            });
+        }
+
+        let rests = !remaining.is_zero() && matches!(order_type, OrderType::GTC | OrderType::GTD);
+        let status = if rests {
+            LIVE
+        } else if remaining.is_zero() {
+            MATCHED
+        } else {
+            CANCELED
+        };
+        self.sequence += 1;
+        self.orders.insert(
+            order_id.clone(),
+            BookEntry {
+                order: OpenOrder {
+                    associate_trades: if filled.is_zero() {
+                        Vec::new()
+                    } else {
+                        vec![trade_id]
+                    },
+                    id: order_id.clone(),
+                    status: status.to_string(),
+                    market: market.condition_id.clone(),
+                    original_size: size,
+                    outcome: market.outcome.clone(),
+                    maker_address: order.maker.clone(),
+                    owner: api_key.to_string(),
+                    price,
+                    side,
+                    size_matched: filled,
+                    asset_id: market.token_id.clone(),
+                    expiration,
+                    order_type,
+                    created_at: now,
+                },
+                sequence: self.sequence,
+            },
+        );
+
+        let (making, taking) = match side {
+            Side::BUY => (notional, filled),
+            Side::SELL => (filled, notional),
+        };
+        Ok(PostOrderResponse {
+            success: true,
+            error_msg: String::new(),
+            order_id,
+            transaction_hashes: Vec::new(),
+            status: Some(if filled.is_zero() {
+                PostOrderStatus::Live
+            } else {
+                PostOrderStatus::Matched
+            }),
+            taking_amount: Some(taking),
+            making_amount: Some(making),
+        })
+    }
+
+    /// Resting orders an incoming order would trade with, in priority order
+    fn crossing_orders(&self, token_id: &str, side: Side, price: Decimal) -> Vec<String> {
+        let mut crossing: Vec<&BookEntry> = self
+            .orders
+            .values()
+            .filter(|entry| {
+                let order = &entry.order;
+                order.status == LIVE
+                    && order.asset_id == token_id
+                    && order.side != side
+                    && match side {
+                        Side::BUY => order.price <= price,
+                        Side::SELL => order.price >= price,
+                    }
+            })
+            .collect();
+
+        crossing.sort_by(|a, b| {
+            let by_price = match side {
+                Side::BUY => a.order.price.cmp(&b.order.price),
+                Side::SELL => b.order.price.cmp(&a.order.price),
+            };
+            by_price.then(a.sequence.cmp(&b.sequence))
+        });
+        crossing
+            .into_iter()
+            .map(|entry| entry.order.id.clone())
+            .collect()
+    }
+
+    /// Drop GTD orders whose expiration is within the security threshold
+    fn expire_orders(&mut self, now: u64) {
+        let cutoff = now + GTD_SECURITY_THRESHOLD.as_secs();
+        for entry in self.orders.values_mut() {
+            let order = &mut entry.order;
+            if order.status == LIVE
+                && order.order_type == OrderType::GTD
+                && order.expiration <= cutoff
+            {
+                order.status = CANCELED.to_string();
+            }
+        }
+    }
+
+    // Cancels
+
+    fn cancel_ids(&mut self, api_key: &str, order_ids: Vec<String>) -> CancelResponse {
+        let mut response = CancelResponse::default();
+        for order_id in order_ids {
+            match self.orders.get_mut(&order_id) {
+                Some(entry) if entry.order.owner == api_key && entry.order.status == LIVE => {
+                    entry.order.status = CANCELED.to_string();
+                    response.canceled.push(order_id);
+                },
+                Some(entry) if entry.order.owner == api_key => {
+                    response.not_canceled.insert(
+                        order_id,
+                        "order can't be found - already canceled or matched".to_string(),
+                    );
+                },
+                _ => {
+                    response
+                        .not_canceled
+                        .insert(order_id, "order not found".to_string());
+                },
+            }
+        }
+        response
+    }
+
+    /// IDs of the caller's resting orders that pass `filter`
+    fn live_orders(&self, api_key: &str, filter: impl Fn(&OpenOrder) -> bool) -> Vec<String> {
+        let mut orders: Vec<&BookEntry> = self
+            .orders
+            .values()
+            .filter(|entry| {
+                entry.order.owner == api_key && entry.order.status == LIVE && filter(&entry.order)
+            })
+            .collect();
+        orders.sort_by_key(|entry| entry.sequence);
+        orders
+            .into_iter()
+            .map(|entry| entry.order.id.clone())
+            .collect()
+    }
+
+    fn cancel(&mut self, request: &MockRequest) -> Handled {
+        let api_key = self.authenticate_l2(request)?;
+        let body: Value = request.json()?;
+        let order_id = body["orderID"]
+            .as_str()
+            .ok_or_else(|| Reply::error(StatusCode::BAD_REQUEST, "Invalid order id"))?;
+
+        Ok(Reply::ok(
+            self.cancel_ids(&api_key, vec![order_id.to_string()]),
+        ))
+    }
+
+    fn cancel_orders(&mut self, request: &MockRequest) -> Handled {
+        let api_key = self.authenticate_l2(request)?;
+        let order_ids: Vec<String> = request.json()?;
+
+        Ok(Reply::ok(self.cancel_ids(&api_key, order_ids)))
+    }
+
+    fn cancel_all(&mut self, request: &MockRequest) -> Handled {
+        let api_key = self.authenticate_l2(request)?;
+        let order_ids = self.live_orders(&api_key, |_| true);
+
+        Ok(Reply::ok(self.cancel_ids(&api_key, order_ids)))
+    }
+
+    fn cancel_market_orders(&mut self, request: &MockRequest) -> Handled {
+        let api_key = self.authenticate_l2(request)?;
+        let body: HashMap<String, String> = request.json()?;
+        let field = |name: &str| body.get(name).filter(|value| !value.is_empty()).cloned();
+        let (market, asset_id) = (field("market"), field("asset_id"));
+
+        let order_ids = self.live_orders(&api_key, |order| {
+            market.as_ref().is_none_or(|market| order.market == *market)
+                && asset_id
+                    .as_ref()
+                    .is_none_or(|asset_id| order.asset_id == *asset_id)
+        });
+        Ok(Reply::ok(self.cancel_ids(&api_key, order_ids)))
+    }
+
+    // Queries
+
+    /// The caller's resting orders, as a single page
+    fn list_orders(&self, request: &MockRequest) -> Handled {
+        let api_key = self.authenticate_l2(request)?;
+        let orders: Vec<&OpenOrder> = self
+            .live_orders(&api_key, |order| {
+                request.query("id").is_none_or(|id| order.id == id)
+                    && request.query("market").is_none_or(|m| order.market == m)
+                    && request
+                        .query("asset_id")
+                        .is_none_or(|asset_id| order.asset_id == asset_id)
+            })
+            .iter()
+            .map(|id| &self.orders[id].order)
+            .collect();
+
+        Ok(Reply::ok(page(orders)))
+    }
+
+    /// Unknown orders are `null`, as on the exchange
+    fn get_order(&self, request: &MockRequest, order_id: &str) -> Handled {
+        let api_key = self.authenticate_l2(request)?;
+        let order = self
+            .orders
+            .get(order_id)
+            .map(|entry| &entry.order)
+            .filter(|order| order.owner == api_key);
+
+        Ok(Reply::ok(order))
+    }
+
+    /// Trades the caller took part in, from the caller's side, as a single page
+    fn list_trades(&self, request: &MockRequest) -> Handled {
+        let api_key = self.authenticate_l2(request)?;
+        let trades: Vec<Trade> = self
+            .trades
+            .iter()
+            .filter(|trade| {
+                request.query("id").is_none_or(|id| trade.id == id)
+                    && request.query("market").is_none_or(|m| trade.market == m)
+                    && request
+                        .query("asset_id")
+                        .is_none_or(|asset_id| trade.asset_id == asset_id)
+            })
+            .filter_map(|trade| {
+                if trade.owner == api_key {
+                    return Some(trade.clone());
+                }
+                trade
+                    .maker_orders
+                    .iter()
+                    .any(|maker| maker.owner == api_key)
+                    .then(|| Trade {
+                        trader_side: Some(TraderSide::MAKER),
+                        ..trade.clone()
+                    })
+            })
+            .collect();
+
+        Ok(Reply::ok(page(trades)))
+    }
+}
+
+/// A final page of results
+fn page<T: Serialize>(data: Vec<T>) -> Value {
+    json!({
+        "limit": data.len(),
+        "count": data.len(),
+        "next_cursor": END_CURSOR,
+        "data": data,
+    })
+}
+
+/// Side, price and size in shares encoded by a signed order's amounts
+fn order_terms(
+    order: &SignedOrderRequest,
+) -> std::result::Result<(Side, Decimal, Decimal), String> {
+    let amount = |field: &str, value: &str| {
+        value
+            .parse::<u64>()
+            .map(math::token_units_to_decimal)
+            .map_err(|_| format!("invalid order payload: {}", field))
+    };
+    let maker = amount("makerAmount", &order.maker_amount)?;
+    let taker = amount("takerAmount", &order.taker_amount)?;
+
+    let (side, collateral, shares) = match order.side.as_str() {
+        "BUY" => (Side::BUY, maker, taker),
+        "SELL" => (Side::SELL, taker, maker),
+        other => return Err(format!("invalid order payload: side {}", other)),
+    };
+    if shares.is_zero() {
+        return Err("invalid order size".to_string());
+    }
+
+    Ok((side, (collateral / shares).normalize(), shares))
+}
@@ -0,0 +1,408 @@
+//! Registry of resting orders placed through the client
+//!
+//! The exchange drops a GTD order silently once it lapses, so a strategy that
+//! only listens for cancels and fills would keep treating it as live. The
+//! registry remembers every resting order the client posted and emits an
+//! `OrderEvent::Expired` once a GTD order's expiration passes.
+
+use crate::clock_sync::ClockSync;
+use crate::types::{
+    CancelResponse, OrderType, PostOrderResponse, PostOrderStatus, Side, SignedOrderRequest,
+    GTD_SECURITY_THRESHOLD,
+};
+use crate::utils::math::token_units_to_decimal;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+/// A resting order known to the registry
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrackedOrder {
+    pub order_id: String,
+    pub token_id: String,
+    /// Condition ID of the order's market, when the client knew it
+    pub market: Option<String>,
+    pub side: Side,
+    pub order_type: OrderType,
+    /// When the order stops resting on the book, for GTD orders
+    pub expires_at: Option<DateTime<Utc>>,
+    pub posted_at: DateTime<Utc>,
+}
+
+impl TrackedOrder {
+    /// Build a tracked order from a posted order and the exchange's reply
+    ///
+    /// Returns `None` unless the exchange accepted the order and left it
+    /// resting on the book. A GTC or GTD order matched on arrival still rests
+    /// when the response reports less than its full size as filled; one
+    /// matched without fill amounts is assumed to be fully filled.
+    pub fn from_posted(
+        order: &SignedOrderRequest,
+        order_type: OrderType,
+        response: &PostOrderResponse,
+        market: Option<String>,
+    ) -> Option<Self> {
+        if !response.is_accepted() {
+            return None;
+        }
+
+        let side = match order.side.as_str() {
+            "BUY" => Side::BUY,
+            "SELL" => Side::SELL,
+            _ => return None,
+        };
+
+        let rests = match response.status {
+            Some(PostOrderStatus::Live) => true,
+            Some(PostOrderStatus::Matched) => {
+                matches!(order_type, OrderType::GTC | OrderType::GTD)
+                    && is_partially_filled(order, side, response)
+            },
+            _ => false,
+        };
+        if !rests {
+            return None;
+        }
+
+        Some(Self {
+            order_id: response.order_id.clone(),
+            token_id: order.token_id.clone(),
+            market,
+            side,
+            order_type,
+            expires_at: lapse_time(&order.expiration),
+            posted_at: Utc::now(),
+        })
+    }
+
+    /// Whether the order has lapsed at `now`
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at <= now)
+    }
+}
+
+/// Lifecycle events emitted by the registry
+#[derive(Debug, Clone, PartialEq)]
+pub enum OrderEvent {
+    /// A GTD order reached its expiration and is no longer on the book
+    Expired(TrackedOrder),
+}
+
+/// Thread-safe registry of resting orders
+#[derive(Debug, Default)]
+pub struct OrderRegistry {
+    orders: RwLock<HashMap<String, TrackedOrder>>,
+    subscribers: Mutex<Vec<mpsc::UnboundedSender<OrderEvent>>>,
+}
+
+impl OrderRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start tracking an order, replacing any entry with the same ID
+    pub fn track(&self, order: TrackedOrder) {
+        self.orders
+            .write()
+            .unwrap()
+            .insert(order.order_id.clone(), order);
+    }
+
+    /// Track an order if the exchange left it resting
+    pub fn track_posted(
+        &self,
+        order: &SignedOrderRequest,
+        order_type: OrderType,
+        response: &PostOrderResponse,
+        market: Option<String>,
+    ) {
+        if let Some(tracked) = TrackedOrder::from_posted(order, order_type, response, market) {
+            self.track(tracked);
+        }
+    }
+
+    /// Stop tracking an order
+    pub fn remove(&self, order_id: &str) -> Option<TrackedOrder> {
+        self.orders.write().unwrap().remove(order_id)
+    }
+
+    /// Stop tracking every order the exchange reported as canceled
+    pub fn apply_cancel(&self, response: &CancelResponse) {
+        let mut orders = self.orders.write().unwrap();
+        for order_id in &response.canceled {
+            orders.remove(order_id);
+        }
+    }
+
+    pub fn get(&self, order_id: &str) -> Option<TrackedOrder> {
+        self.orders.read().unwrap().get(order_id).cloned()
+    }
+
+    /// Every tracked order
+    pub fn orders(&self) -> Vec<TrackedOrder> {
+        self.orders.read().unwrap().values().cloned().collect()
+    }
+
+    /// Tracked orders for a single token
+    pub fn orders_for_token(&self, token_id: &str) -> Vec<TrackedOrder> {
+        self.orders
+            .read()
+            .unwrap()
+            .values()
+            .filter(|order| order.token_id == token_id)
+            .cloned()
+            .collect()
+    }
+
+    /// Tracked orders known to belong to a market
+    ///
+    /// Orders whose market was never resolved are left out.
+    pub fn orders_for_market(&self, condition_id: &str) -> Vec<TrackedOrder> {
+        self.orders
+            .read()
+            .unwrap()
+            .values()
+            .filter(|order| order.market.as_deref() == Some(condition_id))
+            .cloned()
+            .collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.orders.read().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Receive lifecycle events for orders in this registry
+    pub fn subscribe(&self) -> mpsc::UnboundedReceiver<OrderEvent> {
+        let (tx, rx) = mpsc::unbounded_channel();
+        self.subscribers.lock().unwrap().push(tx);
+        rx
+    }
+
+    /// Remove every order that has lapsed at `now` and emit its expiry event
+    pub fn expire_due(&self, now: DateTime<Utc>) -> Vec<TrackedOrder> {
+        let expired: Vec<TrackedOrder> = {
+            let mut orders = self.orders.write().unwrap();
+            let due: Vec<String> = orders
+                .values()
+                .filter(|order| order.is_expired(now))
+                .map(|order| order.order_id.clone())
+                .collect();
+            due.iter().filter_map(|id| orders.remove(id)).collect()
+        };
+
+        for order in &expired {
+            self.emit(OrderEvent::Expired(order.clone()));
+        }
+        expired
+    }
+
+    /// Earliest pending expiration, if any GTD order is tracked
+    pub fn next_expiry(&self) -> Option<DateTime<Utc>> {
+        self.orders
+            .read()
+            .unwrap()
+            .values()
+            .filter_map(|order| order.expires_at)
+            .min()
+    }
+
+    /// Check for lapsed orders every `interval` until the task is aborted
+    ///
+    /// Expirations are judged by the exchange's clock, so pass the clock of
+    /// the client that posts the orders.
+    pub fn spawn_expiry_task(
+        self: &Arc<Self>,
+        clock: Arc<ClockSync>,
+        interval: Duration,
+    ) -> JoinHandle<()> {
+        let registry = Arc::clone(self);
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                registry.expire_due(clock.now());
+            }
+        })
+    }
+
+    fn emit(&self, event: OrderEvent) {
+        self.subscribers
+            .lock()
+            .unwrap()
+            .retain(|tx| tx.send(event.clone()).is_ok());
+    }
+}
+
+/// Whether a matched order filled less than its full size
+///
+/// Buys fill in the shares they take and sells in the shares they make, so
+/// the filled side of the response is compared with the same side of the
+/// signed order.
+fn is_partially_filled(
+    order: &SignedOrderRequest,
+    side: Side,
+    response: &PostOrderResponse,
+) -> bool {
+    let (filled, ordered) = match side {
+        Side::BUY => (response.taking_amount, &order.taker_amount),
+        Side::SELL => (response.making_amount, &order.maker_amount),
+    };
+    match (filled, ordered.parse::<u64>()) {
+        (Some(filled), Ok(ordered)) => filled < token_units_to_decimal(ordered),
+        _ => false,
+    }
+}
+
+/// When an order signed with `expiration` stops resting on the book
+///
+/// The exchange retires GTD orders one security threshold before the signed
+/// expiration. A zero expiration means the order never lapses.
+fn lapse_time(expiration: &str) -> Option<DateTime<Utc>> {
+    let expiration: i64 = expiration.parse().ok().filter(|&ts| ts > 0)?;
+    DateTime::from_timestamp(expiration - GTD_SECURITY_THRESHOLD.as_secs() as i64, 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tracked(order_id: &str, expires_at: Option<DateTime<Utc>>) -> TrackedOrder {
+        TrackedOrder {
+            order_id: order_id.to_string(),
+            token_id: "123".to_string(),
+            market: None,
+            side: Side::BUY,
+            order_type: if expires_at.is_some() {
+                OrderType::GTD
+            } else {
+                OrderType::GTC
+            },
+            expires_at,
+            posted_at: Utc::now(),
+        }
+    }
+
+    fn signed_buy(size_units: &str) -> SignedOrderRequest {
+        SignedOrderRequest {
+            salt: 1,
+            maker: "0x1111111111111111111111111111111111111111".to_string(),
+            signer: "0x1111111111111111111111111111111111111111".to_string(),
+            taker: "0x0000000000000000000000000000000000000000".to_string(),
+            token_id: "123".to_string(),
+            maker_amount: "5000000".to_string(),
+            taker_amount: size_units.to_string(),
+            expiration: "0".to_string(),
+            nonce: "0".to_string(),
+            fee_rate_bps: "0".to_string(),
+            side: "BUY".to_string(),
+            signature_type: 0,
+            signature: "0x".to_string(),
+        }
+    }
+
+    fn matched(shares: &str) -> PostOrderResponse {
+        PostOrderResponse {
+            success: true,
+            error_msg: String::new(),
+            order_id: "0xorder".to_string(),
+            transaction_hashes: Vec::new(),
+            status: Some(PostOrderStatus::Matched),
+            taking_amount: Some(shares.parse().unwrap()),
+            making_amount: None,
+        }
+    }
+
+    #[test]
+    fn test_partially_matched_resting_orders_are_tracked() {
+        let order = signed_buy("10000000");
+
+        let partial = TrackedOrder::from_posted(&order, OrderType::GTC, &matched("4"), None);
+        assert_eq!(partial.unwrap().order_id, "0xorder");
+
+        assert!(TrackedOrder::from_posted(&order, OrderType::GTC, &matched("10"), None).is_none());
+        assert!(TrackedOrder::from_posted(&order, OrderType::FAK, &matched("4"), None).is_none());
+    }
+
+    #[test]
+    fn test_expire_due_emits_events() {
+        let registry = OrderRegistry::new();
+        let mut events = registry.subscribe();
+        let now = Utc::now();
+
+        registry.track(tracked("gtc", None));
+        registry.track(tracked("lapsed", Some(now - chrono::Duration::seconds(1))));
+        registry.track(tracked("later", Some(now + chrono::Duration::hours(1))));
+
+        let expired = registry.expire_due(now);
+        assert_eq!(expired.len(), 1);
+        assert_eq!(expired[0].order_id, "lapsed");
+        assert_eq!(registry.len(), 2);
+        assert_eq!(
+            registry.next_expiry(),
+            Some(now + chrono::Duration::hours(1))
+        );
+
+        match events.try_recv().unwrap() {
+            OrderEvent::Expired(order) => assert_eq!(order.order_id, "lapsed"),
+        }
+        assert!(events.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_expiry_task_uses_exchange_clock() {
+        let registry = Arc::new(OrderRegistry::new());
+        let mut events = registry.subscribe();
+        // Still resting by local time, but lapsed on an exchange an hour ahead
+        registry.track(tracked(
+            "gtd",
+            Some(Utc::now() + chrono::Duration::minutes(30)),
+        ));
+
+        let clock = Arc::new(ClockSync::new());
+        let now = crate::utils::time::now_millis();
+        clock.record(crate::clock_sync::ClockSample {
+            sent_millis: now,
+            server_secs: now / 1000 + 3600,
+            received_millis: now,
+        });
+
+        let task = registry.spawn_expiry_task(clock, Duration::from_millis(10));
+        let event = tokio::time::timeout(Duration::from_secs(5), events.recv())
+            .await
+            .unwrap();
+        task.abort();
+        assert!(matches!(event, Some(OrderEvent::Expired(order)) if order.order_id == "gtd"));
+        assert!(registry.is_empty());
+    }
+
+    #[test]
+    fn test_apply_cancel_untracks_orders() {
+        let registry = OrderRegistry::new();
+        registry.track(tracked("a", None));
+        registry.track(tracked("b", None));
+
+        registry.apply_cancel(&CancelResponse {
+            canceled: vec!["a".to_string()],
+            not_canceled: HashMap::from([("b".to_string(), "not found".to_string())]),
+        });
+
+        assert!(registry.get("a").is_none());
+        assert!(registry.get("b").is_some());
+    }
+
+    #[test]
+    fn test_lapse_time_subtracts_security_threshold() {
+        assert_eq!(lapse_time("0"), None);
+        assert_eq!(
+            lapse_time("1700000060"),
+            DateTime::from_timestamp(1_700_000_000, 0)
+        );
+    }
+}
@@ -5,8 +5,13 @@
 
 use crate::auth::sign_order_message;
 use crate::client::OrderArgs;
-use crate::errors::{PolyfillError, Result};
-use crate::types::{ExtraOrderArgs, MarketOrderArgs, OrderOptions, Side, SignedOrderRequest};
+use crate::errors::{OrderErrorKind, PolyfillError, Result};
+use crate::market_cache::MarketMetadata;
+use crate::types::{
+    is_price_tick_aligned, BalanceAllowance, ExtraOrderArgs, MarketOrderArgs, OrderOptions, Side,
+    SignedOrderRequest,
+};
+use crate::utils::math;
 use alloy_primitives::{Address, U256};
 use alloy_signer_local::PrivateKeySigner;
 use rand::Rng;
@@ -115,6 +120,195 @@ fn generate_seed() -> u64 {
     (timestamp as f64 * y) as u64
 }
 
+/// Derive an order salt from a client order ID
+///
+/// The salt is kept below 2^53 so it survives JSON number handling on the
+/// exchange side unchanged.
+pub fn deterministic_salt(client_order_id: &str) -> u64 {
+    use sha2::{Digest, Sha256};
+
+    let digest = Sha256::digest(client_order_id.as_bytes());
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&digest[..8]);
+    u64::from_be_bytes(bytes) & ((1 << 53) - 1)
+}
+
+/// Compute the exchange order ID of a signed order
+///
+/// This is the EIP-712 hash the order was signed over, as a 0x-prefixed hex
+/// string. `neg_risk` selects the exchange contract, as when the order was built.
+pub fn signed_order_hash(
+    order: &SignedOrderRequest,
+    chain_id: u64,
+    neg_risk: bool,
+) -> Result<String> {
+    let contract_config = get_contract_config(chain_id, neg_risk).ok_or_else(|| {
+        PolyfillError::config("No contract found with given chain_id and neg_risk")
+    })?;
+    let exchange = Address::from_str(&contract_config.exchange)
+        .map_err(|e| PolyfillError::config(format!("Invalid exchange address: {}", e)))?;
+
+    let address = |field: &str, value: &str| {
+        Address::from_str(value).map_err(|e| {
+            PolyfillError::validation_field(format!("Invalid {} address: {}", field, e), field)
+        })
+    };
+    let uint = |field: &str, value: &str| {
+        U256::from_str_radix(value, 10).map_err(|e| {
+            PolyfillError::validation_field(format!("Invalid {}: {}", field, e), field)
+        })
+    };
+    let side = match order.side.as_str() {
+        "BUY" => Side::BUY,
+        "SELL" => Side::SELL,
+        other => {
+            return Err(PolyfillError::validation_field(
+                format!("Invalid side: {}", other),
+                "side",
+            ))
+        },
+    };
+
+    let order = crate::auth::Order {
+        salt: U256::from(order.salt),
+        maker: address("maker", &order.maker)?,
+        signer: address("signer", &order.signer)?,
+        taker: address("taker", &order.taker)?,
+        tokenId: uint("token_id", &order.token_id)?,
+        makerAmount: uint("maker_amount", &order.maker_amount)?,
+        takerAmount: uint("taker_amount", &order.taker_amount)?,
+        expiration: uint("expiration", &order.expiration)?,
+        nonce: uint("nonce", &order.nonce)?,
+        feeRateBps: uint("fee_rate_bps", &order.fee_rate_bps)?,
+        side: side as u8,
+        signatureType: order.signature_type,
+    };
+
+    Ok(crate::auth::order_hash(&order, chain_id, exchange).to_string())
+}
+
+/// Check a market order, priced at `price`, against the market's rules
+///
+/// The order is checked as the limit order it is signed as: a BUY of
+/// `amount / price` shares, or a SELL of `amount` shares.
+pub fn validate_market_order(
+    order_args: &MarketOrderArgs,
+    price: Decimal,
+    metadata: &MarketMetadata,
+    balance: Option<&BalanceAllowance>,
+) -> Result<()> {
+    let size = match order_args.side {
+        Side::BUY if price > Decimal::ZERO => order_args.amount / price,
+        Side::BUY => Decimal::ZERO,
+        Side::SELL => order_args.amount,
+    };
+    let limit_args = OrderArgs::new(&order_args.token_id, price, size, order_args.side);
+    validate_order(&limit_args, metadata, balance)
+}
+
+/// Check a limit order against the market's rules before it is signed
+///
+/// Rules missing from `metadata` are not checked. When `balance` is given it
+/// must cover the order: collateral for a BUY, the outcome token for a SELL.
+/// Balances are in base units (6 decimals), as the exchange reports them.
+pub fn validate_order(
+    order_args: &OrderArgs,
+    metadata: &MarketMetadata,
+    balance: Option<&BalanceAllowance>,
+) -> Result<()> {
+    if metadata.accepting_orders == Some(false) {
+        return Err(PolyfillError::order(
+            format!(
+                "Market for token {} is not accepting orders",
+                order_args.token_id
+            ),
+            OrderErrorKind::MarketClosed,
+        ));
+    }
+
+    if order_args.size <= Decimal::ZERO {
+        return Err(PolyfillError::order(
+            format!("Order size {} must be positive", order_args.size),
+            OrderErrorKind::InvalidSize,
+        ));
+    }
+
+    if let Some(min_order_size) = metadata.min_order_size {
+        if order_args.size < min_order_size {
+            return Err(PolyfillError::order(
+                format!(
+                    "Order size {} below market minimum {}",
+                    order_args.size, min_order_size
+                ),
+                OrderErrorKind::SizeConstraint,
+            ));
+        }
+    }
+
+    if let Some(tick_size) = metadata.tick_size {
+        if !math::is_valid_price(order_args.price, tick_size) {
+            return Err(PolyfillError::order(
+                format!(
+                    "Price {} outside [{}, {}]",
+                    order_args.price,
+                    tick_size,
+                    Decimal::ONE - tick_size
+                ),
+                OrderErrorKind::InvalidPrice,
+            ));
+        }
+        if !is_price_tick_aligned(order_args.price, tick_size) {
+            return Err(PolyfillError::validation_field(
+                format!(
+                    "Price {} is not a multiple of tick size {}",
+                    order_args.price, tick_size
+                ),
+                "price",
+            ));
+        }
+    }
+
+    if let Some(balance) = balance {
+        let required = match order_args.side {
+            Side::BUY => order_args.price * order_args.size,
+            Side::SELL => {
+                if !balance.asset_id.is_empty() && balance.asset_id != order_args.token_id {
+                    return Err(PolyfillError::validation_field(
+                        format!(
+                            "Balance is for asset {}, not token {}",
+                            balance.asset_id, order_args.token_id
+                        ),
+                        "balance",
+                    ));
+                }
+                order_args.size
+            },
+        };
+        let required = Decimal::from(math::decimal_to_token_units(required));
+
+        if balance.balance < required {
+            return Err(PolyfillError::order(
+                format!(
+                    "Balance {} does not cover required {}",
+                    balance.balance, required
+                ),
+                OrderErrorKind::InsufficientBalance,
+            ));
+        }
+        if balance.allowance < required {
+            return Err(PolyfillError::order(
+                format!(
+                    "Allowance {} does not cover required {}",
+                    balance.allowance, required
+                ),
+                OrderErrorKind::InsufficientBalance,
+            ));
+        }
+    }
+
+    Ok(())
+}
+
 /// Convert decimal to token units (multiply by 1e6)
 fn decimal_to_token_u32(amt: Decimal) -> u32 {
     let mut amt = Decimal::from_scientific("1e6").expect("1e6 is not scientific") * amt;
@@ -191,12 +385,20 @@ impl OrderBuilder {
     }
 
     /// Get order amounts for a market order
+    ///
+    /// A BUY spends `amount` collateral; a SELL sells `amount` shares and is
+    /// rounded exactly like a limit SELL of that size.
     fn get_market_order_amounts(
         &self,
+        side: Side,
         amount: Decimal,
         price: Decimal,
         round_config: &RoundConfig,
     ) -> (u32, u32) {
+        if side == Side::SELL {
+            return self.get_order_amounts(Side::SELL, amount, price, round_config);
+        }
+
         let raw_maker_amt = amount.round_dp_with_strategy(round_config.size, ToZero);
         let raw_price = price.round_dp_with_strategy(round_config.price, MidpointTowardZero);
 
@@ -210,15 +412,22 @@ impl OrderBuilder {
     }
 
     /// Calculate market price from order book levels
+    ///
+    /// `positions` must be ordered best price first: asks for a BUY, bids for
+    /// a SELL. A BUY matches `amount_to_match` in collateral, a SELL in shares.
     pub fn calculate_market_price(
         &self,
+        side: Side,
         positions: &[crate::types::BookLevel],
         amount_to_match: Decimal,
     ) -> Result<Decimal> {
         let mut sum = Decimal::ZERO;
 
         for level in positions {
-            sum += level.size * level.price;
+            sum += match side {
+                Side::BUY => level.size * level.price,
+                Side::SELL => level.size,
+            };
             if sum >= amount_to_match {
                 return Ok(level.price);
             }
@@ -246,8 +455,24 @@ impl OrderBuilder {
             .tick_size
             .ok_or_else(|| PolyfillError::validation("Cannot create order without tick size"))?;
 
-        let (maker_amount, taker_amount) =
-            self.get_market_order_amounts(order_args.amount, price, &ROUNDING_CONFIG[&tick_size]);
+        if !order_args.accepts_price(price) {
+            return Err(PolyfillError::order(
+                format!(
+                    "Market price {} is worse than the limit {} for a {} order",
+                    price,
+                    order_args.worst_price.unwrap_or_default(),
+                    order_args.side.as_str()
+                ),
+                crate::errors::OrderErrorKind::PriceConstraint,
+            ));
+        }
+
+        let (maker_amount, taker_amount) = self.get_market_order_amounts(
+            order_args.side,
+            order_args.amount,
+            price,
+            &ROUNDING_CONFIG[&tick_size],
+        );
 
         let neg_risk = options
             .neg_risk
@@ -262,7 +487,7 @@ impl OrderBuilder {
 
         self.build_signed_order(
             order_args.token_id.clone(),
-            Side::BUY,
+            order_args.side,
             chain_id,
             exchange_address,
             maker_amount,
@@ -334,7 +559,7 @@ impl OrderBuilder {
         expiration: u64,
         extras: &ExtraOrderArgs,
     ) -> Result<SignedOrderRequest> {
-        let seed = generate_seed();
+        let seed = extras.salt.unwrap_or_else(generate_seed);
         let taker_address = Address::from_str(&extras.taker)
             .map_err(|e| PolyfillError::validation(format!("Invalid taker address: {}", e)))?;
 
@@ -443,4 +668,239 @@ mod tests {
             assert!(seed < u64::MAX);
         }
     }
+
+    fn test_builder() -> OrderBuilder {
+        OrderBuilder::new(PrivateKeySigner::random(), None, None)
+    }
+
+    fn level(price: &str, size: &str) -> crate::types::BookLevel {
+        crate::types::BookLevel {
+            price: Decimal::from_str(price).unwrap(),
+            size: Decimal::from_str(size).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_deterministic_salt() {
+        let salt = deterministic_salt("client-order-1");
+        assert_eq!(salt, deterministic_salt("client-order-1"));
+        assert_ne!(salt, deterministic_salt("client-order-2"));
+        assert!(salt < 1 << 53);
+    }
+
+    #[test]
+    fn test_signed_order_hash_is_the_signed_digest() {
+        let builder = test_builder();
+        let order_args = OrderArgs::new(
+            "123",
+            Decimal::from_str("0.55").unwrap(),
+            Decimal::from(10),
+            Side::BUY,
+        );
+        let extras = ExtraOrderArgs::default().with_client_order_id("client-order-1");
+        let options = OrderOptions {
+            tick_size: Some(Decimal::from_str("0.01").unwrap()),
+            neg_risk: Some(false),
+            fee_rate_bps: None,
+        };
+
+        let order = builder
+            .create_order(137, &order_args, 0, &extras, &options)
+            .unwrap();
+        let again = builder
+            .create_order(137, &order_args, 0, &extras, &options)
+            .unwrap();
+        assert_eq!(order.salt, deterministic_salt("client-order-1"));
+        assert_eq!(order.signature, again.signature);
+
+        let hash = signed_order_hash(&order, 137, false).unwrap();
+        assert_eq!(hash.len(), 66);
+        assert_eq!(hash, signed_order_hash(&again, 137, false).unwrap());
+
+        // The order was signed over exactly this digest
+        let signature = alloy_primitives::PrimitiveSignature::from_str(&order.signature).unwrap();
+        let recovered = signature
+            .recover_address_from_prehash(&alloy_primitives::B256::from_str(&hash).unwrap())
+            .unwrap();
+        assert_eq!(recovered, builder.signer.address());
+
+        // The neg-risk exchange is a different EIP-712 domain
+        assert_ne!(hash, signed_order_hash(&order, 137, true).unwrap());
+    }
+
+    #[test]
+    fn test_market_order_amounts_by_side() {
+        let builder = test_builder();
+        let config = &ROUNDING_CONFIG[&Decimal::from_str("0.01").unwrap()];
+        let price = Decimal::from_str("0.55").unwrap();
+
+        // BUY spends 100 USDC and receives shares
+        let (maker, taker) =
+            builder.get_market_order_amounts(Side::BUY, Decimal::from(100), price, config);
+        assert_eq!(maker, 100_000_000);
+        assert_eq!(taker, 181_818_100);
+
+        // SELL gives up shares, rounded down to size precision, for USDC
+        let amount = Decimal::from_str("100.129").unwrap();
+        let (maker, taker) = builder.get_market_order_amounts(Side::SELL, amount, price, config);
+        assert_eq!(maker, 100_120_000);
+        assert_eq!(taker, 55_066_000);
+    }
+
+    #[test]
+    fn test_calculate_market_price_sell_walks_bids_in_shares() {
+        let builder = test_builder();
+        let bids = [level("0.50", "50"), level("0.48", "100")];
+
+        let price = builder
+            .calculate_market_price(Side::SELL, &bids, Decimal::from(50))
+            .unwrap();
+        assert_eq!(price, Decimal::from_str("0.50").unwrap());
+
+        let price = builder
+            .calculate_market_price(Side::SELL, &bids, Decimal::from(120))
+            .unwrap();
+        assert_eq!(price, Decimal::from_str("0.48").unwrap());
+
+        assert!(builder
+            .calculate_market_price(Side::SELL, &bids, Decimal::from(200))
+            .is_err());
+    }
+
+    fn order_kind(result: Result<()>) -> Option<OrderErrorKind> {
+        match result {
+            Err(PolyfillError::Order { kind, .. }) => Some(kind),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_validate_order_market_rules() {
+        let metadata = MarketMetadata {
+            tick_size: Some(Decimal::from_str("0.01").unwrap()),
+            min_order_size: Some(Decimal::from(5)),
+            accepting_orders: Some(true),
+            ..Default::default()
+        };
+        let args = |price: &str, size: u32| {
+            OrderArgs::new(
+                "123",
+                Decimal::from_str(price).unwrap(),
+                Decimal::from(size),
+                Side::BUY,
+            )
+        };
+
+        assert!(validate_order(&args("0.55", 10), &metadata, None).is_ok());
+        assert_eq!(
+            order_kind(validate_order(&args("0.55", 2), &metadata, None)),
+            Some(OrderErrorKind::SizeConstraint)
+        );
+        assert_eq!(
+            order_kind(validate_order(&args("0.55", 0), &metadata, None)),
+            Some(OrderErrorKind::InvalidSize)
+        );
+        assert_eq!(
+            order_kind(validate_order(&args("0.995", 10), &metadata, None)),
+            Some(OrderErrorKind::InvalidPrice)
+        );
+        match validate_order(&args("0.555", 10), &metadata, None) {
+            Err(PolyfillError::Validation { field, .. }) => {
+                assert_eq!(field.as_deref(), Some("price"))
+            },
+            other => panic!("expected a price validation error, got {:?}", other),
+        }
+
+        let closed = MarketMetadata {
+            accepting_orders: Some(false),
+            ..metadata.clone()
+        };
+        assert_eq!(
+            order_kind(validate_order(&args("0.55", 10), &closed, None)),
+            Some(OrderErrorKind::MarketClosed)
+        );
+
+        // Unknown rules are not enforced
+        assert!(validate_order(&args("0.555", 1), &MarketMetadata::default(), None).is_ok());
+    }
+
+    #[test]
+    fn test_validate_market_order_in_shares() {
+        let metadata = MarketMetadata {
+            tick_size: Some(Decimal::from_str("0.01").unwrap()),
+            min_order_size: Some(Decimal::from(5)),
+            ..Default::default()
+        };
+        let price = Decimal::from_str("0.5").unwrap();
+
+        // A 2 USDC BUY at 0.50 is 4 shares, below the 5 share minimum
+        let buy = MarketOrderArgs::buy("123", Decimal::from(2));
+        assert_eq!(
+            order_kind(validate_market_order(&buy, price, &metadata, None)),
+            Some(OrderErrorKind::SizeConstraint)
+        );
+        let buy = MarketOrderArgs::buy("123", Decimal::from(3));
+        assert!(validate_market_order(&buy, price, &metadata, None).is_ok());
+
+        // The BUY needs its notional in collateral, a SELL its shares
+        let balance = BalanceAllowance {
+            asset_id: String::new(),
+            balance: Decimal::from(2_999_999),
+            allowance: Decimal::from(10_000_000),
+        };
+        assert_eq!(
+            order_kind(validate_market_order(
+                &buy,
+                price,
+                &metadata,
+                Some(&balance)
+            )),
+            Some(OrderErrorKind::InsufficientBalance)
+        );
+        let sell = MarketOrderArgs::sell("123", Decimal::from(4));
+        assert_eq!(
+            order_kind(validate_market_order(&sell, price, &metadata, None)),
+            Some(OrderErrorKind::SizeConstraint)
+        );
+    }
+
+    #[test]
+    fn test_validate_order_balance() {
+        let check = |side: Side, asset_id: &str, balance: u64, allowance: u64| {
+            let args = OrderArgs::new(
+                "123",
+                Decimal::from_str("0.5").unwrap(),
+                Decimal::from(10),
+                side,
+            );
+            let balance = BalanceAllowance {
+                asset_id: asset_id.to_string(),
+                balance: Decimal::from(balance),
+                allowance: Decimal::from(allowance),
+            };
+            validate_order(&args, &MarketMetadata::default(), Some(&balance))
+        };
+
+        // A BUY of 10 at 0.50 needs 5 USDC of collateral
+        assert!(check(Side::BUY, "", 5_000_000, 5_000_000).is_ok());
+        assert_eq!(
+            order_kind(check(Side::BUY, "", 4_999_999, 10_000_000)),
+            Some(OrderErrorKind::InsufficientBalance)
+        );
+        assert_eq!(
+            order_kind(check(Side::BUY, "", 10_000_000, 0)),
+            Some(OrderErrorKind::InsufficientBalance)
+        );
+
+        // A SELL of 10 needs 10 outcome tokens of the same asset
+        assert!(check(Side::SELL, "123", 10_000_000, 10_000_000).is_ok());
+        assert_eq!(
+            order_kind(check(Side::SELL, "123", 9_000_000, 10_000_000)),
+            Some(OrderErrorKind::InsufficientBalance)
+        );
+        assert!(matches!(
+            check(Side::SELL, "456", 10_000_000, 10_000_000),
+            Err(PolyfillError::Validation { .. })
+        ));
+    }
 }
@@ -0,0 +1,314 @@
+//! Cursor-based pagination for Polymarket list endpoints
+//!
+//! The CLOB paginates every list endpoint with an opaque `next_cursor`.
+//! [`Paginator`] wraps a page-fetching closure and exposes the result as a
+//! `futures::Stream` of individual items, so callers can consume large result
+//! sets without holding every page in memory. The Gamma and data APIs page
+//! by offset instead; [`Paginator::with_offsets`] covers those, using the
+//! offset of the next page as the cursor.
+
+use crate::errors::{PolyfillError, Result};
+use futures::future::BoxFuture;
+use futures::Stream;
+use serde::Deserialize;
+use std::collections::VecDeque;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// Cursor that requests the first page
+pub const INITIAL_CURSOR: &str = "MA==";
+
+/// Cursor returned by the exchange once the last page has been served
+pub const END_CURSOR: &str = "LTE=";
+
+/// A single page returned by a cursor-based endpoint
+#[derive(Debug, Clone, Deserialize)]
+pub struct Page<T> {
+    #[serde(default = "Vec::new")]
+    pub data: Vec<T>,
+    #[serde(default)]
+    pub next_cursor: Option<String>,
+}
+
+impl<T> Page<T> {
+    /// Check whether this is the final page
+    pub fn is_last(&self) -> bool {
+        is_end_cursor(self.next_cursor.as_deref())
+    }
+}
+
+/// Check whether a cursor marks the end of a result set
+pub fn is_end_cursor(cursor: Option<&str>) -> bool {
+    matches!(cursor, None | Some("") | Some(END_CURSOR))
+}
+
+type PageFetcher<'a, T> = Box<dyn FnMut(String) -> BoxFuture<'a, Result<Page<T>>> + Send + 'a>;
+
+/// Stream of items from a cursor-based endpoint
+///
+/// Pages are fetched lazily, one request at a time, as the stream is polled.
+/// Dropping the stream (or using `StreamExt::take`) stops pagination early,
+/// and [`Paginator::next_cursor`] returns the cursor to resume from later.
+///
+/// If a page request fails, the error is yielded once and the stream ends.
+/// The cursor of the failed page is kept so the caller can retry from it.
+pub struct Paginator<'a, T> {
+    fetch: PageFetcher<'a, T>,
+    next_cursor: Option<String>,
+    buffer: VecDeque<T>,
+    in_flight: Option<BoxFuture<'a, Result<Page<T>>>>,
+    pages_fetched: usize,
+    max_pages: Option<usize>,
+    done: bool,
+}
+
+impl<'a, T: Send + 'a> Paginator<'a, T> {
+    /// Create a paginator starting at `start_cursor` (or the first page)
+    ///
+    /// `fetch` receives the cursor of the page to load and returns that page.
+    pub fn new<F, Fut>(start_cursor: Option<&str>, mut fetch: F) -> Self
+    where
+        F: FnMut(String) -> Fut + Send + 'a,
+        Fut: Future<Output = Result<Page<T>>> + Send + 'a,
+    {
+        let start_cursor = start_cursor.unwrap_or(INITIAL_CURSOR);
+
+        Self {
+            fetch: Box::new(move |cursor| Box::pin(fetch(cursor))),
+            done: is_end_cursor(Some(start_cursor)),
+            next_cursor: Some(start_cursor.to_string()),
+            buffer: VecDeque::new(),
+            in_flight: None,
+            pages_fetched: 0,
+            max_pages: None,
+        }
+    }
+
+    /// Create a paginator over an offset-based endpoint
+    ///
+    /// `fetch` receives the offset and size of the page to load and returns
+    /// its items. A page shorter than `limit` ends the listing.
+    pub fn with_offsets<F, Fut>(start_offset: u32, limit: u32, mut fetch: F) -> Self
+    where
+        F: FnMut(u32, u32) -> Fut + Send + 'a,
+        Fut: Future<Output = Result<Vec<T>>> + Send + 'a,
+    {
+        let limit = limit.max(1);
+        Self::new(Some(&start_offset.to_string()), move |cursor: String| {
+            let page = cursor
+                .parse::<u32>()
+                .map(|offset| (offset, fetch(offset, limit)));
+            async move {
+                let (offset, page) = page.map_err(|_| {
+                    PolyfillError::validation(format!("Invalid offset cursor: {}", cursor))
+                })?;
+                let data = page.await?;
+                let next_cursor =
+                    (data.len() as u32 >= limit).then(|| (offset + data.len() as u32).to_string());
+                Ok(Page { data, next_cursor })
+            }
+        })
+    }
+
+    /// Stop after fetching at most `max_pages` pages
+    pub fn with_max_pages(mut self, max_pages: usize) -> Self {
+        self.max_pages = Some(max_pages);
+        self
+    }
+
+    /// Cursor of the next page that has not been fetched yet
+    ///
+    /// Returns `None` once the final page has been fetched. Items from the
+    /// current page that are still buffered are not covered by this cursor.
+    pub fn next_cursor(&self) -> Option<&str> {
+        self.next_cursor.as_deref()
+    }
+
+    /// Number of pages fetched so far
+    pub fn pages_fetched(&self) -> usize {
+        self.pages_fetched
+    }
+
+    /// Fetch the next whole page, bypassing item-level streaming
+    ///
+    /// Any items still buffered from a previous page are returned first.
+    pub async fn next_page(&mut self) -> Option<Result<Vec<T>>> {
+        if !self.buffer.is_empty() {
+            return Some(Ok(self.buffer.drain(..).collect()));
+        }
+
+        let cursor = self.take_cursor()?;
+        let result = (self.fetch)(cursor.clone()).await;
+        Some(
+            self.handle_page(cursor, result)
+                .map(|_| self.buffer.drain(..).collect()),
+        )
+    }
+
+    fn take_cursor(&mut self) -> Option<String> {
+        if self.done || self.max_pages.is_some_and(|max| self.pages_fetched >= max) {
+            return None;
+        }
+        self.next_cursor.clone()
+    }
+
+    fn handle_page(&mut self, cursor: String, result: Result<Page<T>>) -> Result<()> {
+        match result {
+            Ok(page) => {
+                self.pages_fetched += 1;
+                // Guard against an endpoint echoing the same cursor forever
+                if page.is_last() || page.next_cursor.as_deref() == Some(cursor.as_str()) {
+                    self.done = true;
+                    self.next_cursor = None;
+                } else {
+                    self.next_cursor = page.next_cursor;
+                }
+                self.buffer.extend(page.data);
+                Ok(())
+            },
+            Err(err) => {
+                self.done = true;
+                self.next_cursor = Some(cursor);
+                Err(err)
+            },
+        }
+    }
+}
+
+// Items are only ever moved out of the buffer, never pinned in place
+impl<T> Unpin for Paginator<'_, T> {}
+
+impl<'a, T: Send + 'a> Stream for Paginator<'a, T> {
+    type Item = Result<T>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        loop {
+            if let Some(item) = this.buffer.pop_front() {
+                return Poll::Ready(Some(Ok(item)));
+            }
+
+            if this.in_flight.is_none() {
+                match this.take_cursor() {
+                    Some(cursor) => this.in_flight = Some((this.fetch)(cursor)),
+                    None => return Poll::Ready(None),
+                }
+            }
+
+            let future = this
+                .in_flight
+                .as_mut()
+                .expect("in-flight request set above");
+            let result = match future.as_mut().poll(cx) {
+                Poll::Ready(result) => result,
+                Poll::Pending => return Poll::Pending,
+            };
+            this.in_flight = None;
+
+            let cursor = this.next_cursor.clone().unwrap_or_default();
+            if let Err(err) = this.handle_page(cursor, result) {
+                return Poll::Ready(Some(Err(err)));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::{StreamExt, TryStreamExt};
+
+    fn numbered_page(cursor: &str) -> Result<Page<u32>> {
+        match cursor {
+            INITIAL_CURSOR => Ok(Page {
+                data: vec![1, 2],
+                next_cursor: Some("Mg==".to_string()),
+            }),
+            "Mg==" => Ok(Page {
+                data: vec![3, 4],
+                next_cursor: Some("NA==".to_string()),
+            }),
+            "NA==" => Ok(Page {
+                data: vec![5],
+                next_cursor: Some(END_CURSOR.to_string()),
+            }),
+            _ => Err(PolyfillError::validation("unknown cursor")),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_paginator_yields_all_items() {
+        let paginator = Paginator::new(None, |cursor| async move { numbered_page(&cursor) });
+        let items: Vec<u32> = paginator.try_collect().await.unwrap();
+        assert_eq!(items, vec![1, 2, 3, 4, 5]);
+    }
+
+    #[tokio::test]
+    async fn test_paginator_resumes_from_cursor() {
+        let paginator =
+            Paginator::new(Some("Mg=="), |cursor| async move { numbered_page(&cursor) });
+        let items: Vec<u32> = paginator.try_collect().await.unwrap();
+        assert_eq!(items, vec![3, 4, 5]);
+    }
+
+    #[tokio::test]
+    async fn test_paginator_early_termination() {
+        let mut paginator = Paginator::new(None, |cursor| async move { numbered_page(&cursor) });
+        let first: Vec<u32> = (&mut paginator).take(2).try_collect().await.unwrap();
+
+        assert_eq!(first, vec![1, 2]);
+        assert_eq!(paginator.pages_fetched(), 1);
+        assert_eq!(paginator.next_cursor(), Some("Mg=="));
+    }
+
+    #[tokio::test]
+    async fn test_paginator_max_pages_and_next_page() {
+        let mut paginator =
+            Paginator::new(None, |cursor| async move { numbered_page(&cursor) }).with_max_pages(2);
+
+        assert_eq!(paginator.next_page().await.unwrap().unwrap(), vec![1, 2]);
+        assert_eq!(paginator.next_page().await.unwrap().unwrap(), vec![3, 4]);
+        assert!(paginator.next_page().await.is_none());
+        assert_eq!(paginator.next_cursor(), Some("NA=="));
+    }
+
+    #[tokio::test]
+    async fn test_paginator_error_keeps_cursor() {
+        let paginator = Paginator::new(Some("bad"), |cursor| async move { numbered_page(&cursor) });
+        let results: Vec<Result<u32>> = paginator.collect().await;
+
+        assert_eq!(results.len(), 1);
+        assert!(results[0].is_err());
+    }
+
+    #[tokio::test]
+    async fn test_offset_paginator_stops_on_short_page() {
+        let items: Vec<u32> = (1..=5).collect();
+        let mut paginator = Paginator::with_offsets(1, 2, |offset, limit| {
+            let page = items
+                .iter()
+                .skip(offset as usize)
+                .take(limit as usize)
+                .copied()
+                .collect();
+            async move { Ok(page) }
+        });
+
+        assert_eq!(paginator.next_page().await.unwrap().unwrap(), vec![2, 3]);
+        assert_eq!(paginator.next_cursor(), Some("3"));
+        let rest: Vec<u32> = paginator.try_collect().await.unwrap();
+        assert_eq!(rest, vec![4, 5]);
+    }
+
+    #[tokio::test]
+    async fn test_paginator_starting_at_end_cursor_is_empty() {
+        let paginator = Paginator::new(
+            Some(END_CURSOR),
+            |cursor| async move { numbered_page(&cursor) },
+        );
+        let items: Vec<u32> = paginator.try_collect().await.unwrap();
+        assert!(items.is_empty());
+    }
+}
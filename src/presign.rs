@@ -0,0 +1,472 @@
+//! Orders signed ahead of time, ready to post the moment a signal fires
+//!
+//! EIP-712 signing is the slowest step between deciding to trade and the
+//! order leaving the process. A [`PresignedOrderPool`] holds a ladder of
+//! signed orders keyed by token, side, price and size, so the hot path is a
+//! hash lookup. [`PresignedOrderPool::refill`] (or the task started by
+//! [`PresignedOrderPool::spawn_refill_task`]) tops every key back up to the
+//! configured depth and drops orders that are about to lapse or were signed
+//! with a nonce that is no longer current. A key that fails to sign is
+//! skipped and reported without holding up the rest.
+
+use crate::client::{ClobClient, OrderArgs};
+use crate::clock_sync::ClockSync;
+use crate::errors::PolyfillError;
+use crate::types::{
+    ExtraOrderArgs, OrderExpiration, OrderType, Side, SignedOrderRequest, GTD_SECURITY_THRESHOLD,
+};
+use alloy_primitives::U256;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tracing::warn;
+
+/// Pre-signing configuration
+#[derive(Debug, Clone)]
+pub struct PresignConfig {
+    /// Signed orders kept ready per key
+    pub depth: usize,
+    /// Order type the orders will be posted as
+    pub order_type: OrderType,
+    /// How long each order rests once signed; required for GTD orders
+    pub expiration: Option<Duration>,
+    /// Orders with less time than this left before they lapse are discarded
+    pub min_lifetime: Duration,
+    /// How often the refill task tops the pool up
+    pub refill_interval: Duration,
+}
+
+impl Default for PresignConfig {
+    fn default() -> Self {
+        Self {
+            depth: 2,
+            order_type: OrderType::GTC,
+            expiration: None,
+            min_lifetime: Duration::from_secs(30),
+            refill_interval: Duration::from_secs(1),
+        }
+    }
+}
+
+/// What a pre-signed order trades
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PresignKey {
+    pub token_id: String,
+    pub side: Side,
+    pub price: Decimal,
+    pub size: Decimal,
+}
+
+impl PresignKey {
+    pub fn new(token_id: &str, side: Side, price: Decimal, size: Decimal) -> Self {
+        Self {
+            token_id: token_id.to_string(),
+            side,
+            price: price.normalize(),
+            size: size.normalize(),
+        }
+    }
+
+    fn order_args(&self) -> OrderArgs {
+        OrderArgs::new(&self.token_id, self.price, self.size, self.side)
+    }
+}
+
+/// A signed order waiting in the pool
+#[derive(Debug, Clone)]
+pub struct PresignedOrder {
+    pub order: SignedOrderRequest,
+    pub order_type: OrderType,
+    pub signed_at: DateTime<Utc>,
+}
+
+impl PresignedOrder {
+    /// When the exchange would stop resting the order, if it ever does
+    ///
+    /// GTD orders are retired one security threshold before their expiration.
+    pub fn lapses_at(&self) -> Option<DateTime<Utc>> {
+        let expiration: i64 = self.order.expiration.parse().ok().filter(|&ts| ts > 0)?;
+        DateTime::from_timestamp(expiration - GTD_SECURITY_THRESHOLD.as_secs() as i64, 0)
+    }
+}
+
+/// Outcome of one [`PresignedOrderPool::refill`]
+#[derive(Debug, Default)]
+pub struct RefillReport {
+    /// Orders signed and added to the pool
+    pub signed: usize,
+    /// Keys that could not be signed, with the error each one hit
+    pub failed: Vec<(PresignKey, PolyfillError)>,
+}
+
+#[derive(Debug, Default)]
+struct PoolState {
+    orders: HashMap<PresignKey, VecDeque<PresignedOrder>>,
+    /// Nonce new orders are signed with; orders with any other are stale
+    nonce: U256,
+}
+
+/// Signed orders ready to post, keyed by token, side, price and size
+#[derive(Debug)]
+pub struct PresignedOrderPool {
+    config: PresignConfig,
+    state: Mutex<PoolState>,
+    /// Clock lapse times are judged by
+    clock: Arc<ClockSync>,
+}
+
+impl PresignedOrderPool {
+    /// Empty pool that judges lapse times by local time
+    ///
+    /// Use [`with_clock`](Self::with_clock) to judge them by the exchange's.
+    pub fn new(config: PresignConfig) -> Self {
+        Self {
+            config,
+            state: Mutex::new(PoolState::default()),
+            clock: Arc::new(ClockSync::new()),
+        }
+    }
+
+    /// Judge lapse times by `clock`, normally that of the signing client
+    pub fn with_clock(mut self, clock: Arc<ClockSync>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    pub fn config(&self) -> &PresignConfig {
+        &self.config
+    }
+
+    /// Keep orders for `key` signed; they are signed on the next refill
+    pub fn add(&self, key: PresignKey) {
+        self.state.lock().unwrap().orders.entry(key).or_default();
+    }
+
+    /// Keep orders signed for every combination of `prices` and `sizes`
+    pub fn add_ladder(&self, token_id: &str, side: Side, prices: &[Decimal], sizes: &[Decimal]) {
+        for price in prices {
+            for size in sizes {
+                self.add(PresignKey::new(token_id, side, *price, *size));
+            }
+        }
+    }
+
+    /// Stop keeping orders for `key`, dropping those already signed
+    pub fn remove(&self, key: &PresignKey) {
+        self.state.lock().unwrap().orders.remove(key);
+    }
+
+    /// Stop keeping orders for every key of `token_id`
+    pub fn remove_token(&self, token_id: &str) {
+        self.state
+            .lock()
+            .unwrap()
+            .orders
+            .retain(|key, _| key.token_id != token_id);
+    }
+
+    /// Every key the pool keeps orders for
+    pub fn keys(&self) -> Vec<PresignKey> {
+        self.state.lock().unwrap().orders.keys().cloned().collect()
+    }
+
+    /// Signed orders ready for `key`, including any not yet found stale
+    pub fn available(&self, key: &PresignKey) -> usize {
+        let state = self.state.lock().unwrap();
+        state.orders.get(key).map_or(0, VecDeque::len)
+    }
+
+    /// Take a signed order for `key`, oldest first
+    ///
+    /// Orders that lapse within `min_lifetime` or carry a stale nonce are
+    /// dropped on the way. Returns `None` when nothing usable is left.
+    pub fn take(&self, key: &PresignKey) -> Option<PresignedOrder> {
+        let mut state = self.state.lock().unwrap();
+        let nonce = state.nonce.to_string();
+        let cutoff = self.lifetime_cutoff(self.clock.now());
+        let orders = state.orders.get_mut(key)?;
+
+        while let Some(order) = orders.pop_front() {
+            if is_usable(&order, &nonce, cutoff) {
+                return Some(order);
+            }
+        }
+        None
+    }
+
+    /// Nonce new orders are signed with
+    pub fn nonce(&self) -> U256 {
+        self.state.lock().unwrap().nonce
+    }
+
+    /// Sign with `nonce` from now on and drop orders signed with any other
+    ///
+    /// Call this after incrementing the exchange nonce on chain, which
+    /// invalidates every order signed with an older one.
+    pub fn set_nonce(&self, nonce: U256) -> usize {
+        self.state.lock().unwrap().nonce = nonce;
+        self.discard_stale(self.clock.now())
+    }
+
+    /// Drop orders that lapse within `min_lifetime` of `now` or carry a stale nonce
+    ///
+    /// Returns the number of orders dropped.
+    pub fn discard_stale(&self, now: DateTime<Utc>) -> usize {
+        let mut state = self.state.lock().unwrap();
+        let nonce = state.nonce.to_string();
+        let cutoff = self.lifetime_cutoff(now);
+
+        let mut discarded = 0;
+        for orders in state.orders.values_mut() {
+            let before = orders.len();
+            orders.retain(|order| is_usable(order, &nonce, cutoff));
+            discarded += before - orders.len();
+        }
+        discarded
+    }
+
+    /// Drop stale orders and sign new ones until every key is at `depth`
+    ///
+    /// Signing runs without holding the pool lock, so orders can be taken
+    /// while a refill is in progress. A key whose order fails to sign keeps
+    /// what was signed before the failure and the refill moves on.
+    pub async fn refill(&self, client: &ClobClient) -> RefillReport {
+        self.discard_stale(self.clock.now());
+        let (nonce, missing): (U256, Vec<(PresignKey, usize)>) = {
+            let state = self.state.lock().unwrap();
+            let missing = state
+                .orders
+                .iter()
+                .filter(|(_, orders)| orders.len() < self.config.depth)
+                .map(|(key, orders)| (key.clone(), self.config.depth - orders.len()))
+                .collect();
+            (state.nonce, missing)
+        };
+
+        let mut report = RefillReport::default();
+        for (key, count) in missing {
+            let order_args = key.order_args();
+            let mut batch = Vec::with_capacity(count);
+            for _ in 0..count {
+                let extras = ExtraOrderArgs {
+                    nonce,
+                    ..client.default_extras()
+                };
+                let order = match client
+                    .create_order(
+                        &order_args,
+                        self.config.expiration.map(OrderExpiration::After),
+                        Some(extras),
+                        None,
+                    )
+                    .await
+                {
+                    Ok(order) => order,
+                    Err(e) => {
+                        warn!("Failed to pre-sign order for {:?}: {}", key, e);
+                        report.failed.push((key.clone(), e));
+                        break;
+                    },
+                };
+                batch.push(PresignedOrder {
+                    order,
+                    order_type: self.config.order_type,
+                    signed_at: Utc::now(),
+                });
+            }
+
+            // The key may have been removed or the nonce moved on while signing
+            let mut state = self.state.lock().unwrap();
+            if state.nonce != nonce {
+                break;
+            }
+            if let Some(orders) = state.orders.get_mut(&key) {
+                report.signed += batch.len();
+                orders.extend(batch);
+            }
+        }
+        report
+    }
+
+    /// Refill from `client` every `refill_interval` until the task is aborted
+    pub fn spawn_refill_task(self: &Arc<Self>, client: Arc<ClobClient>) -> JoinHandle<()> {
+        let pool = Arc::clone(self);
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(pool.config.refill_interval);
+            loop {
+                ticker.tick().await;
+                pool.refill(&client).await;
+            }
+        })
+    }
+
+    /// Orders lapsing at or before this time are no longer worth posting
+    ///
+    /// Saturates at the latest representable time for huge `min_lifetime`s.
+    fn lifetime_cutoff(&self, now: DateTime<Utc>) -> DateTime<Utc> {
+        chrono::Duration::from_std(self.config.min_lifetime)
+            .ok()
+            .and_then(|min_lifetime| now.checked_add_signed(min_lifetime))
+            .unwrap_or(DateTime::<Utc>::MAX_UTC)
+    }
+}
+
+fn is_usable(order: &PresignedOrder, nonce: &str, cutoff: DateTime<Utc>) -> bool {
+    order.order.nonce == nonce && order.lapses_at().is_none_or(|lapses_at| lapses_at > cutoff)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::ApiCredentials;
+    use rust_decimal_macros::dec;
+
+    fn test_client() -> ClobClient {
+        let client = ClobClient::builder("http://127.0.0.1:9")
+            .private_key("0x1234567890123456789012345678901234567890123456789012345678901234")
+            .api_creds(ApiCredentials {
+                api_key: "test_key".to_string(),
+                secret: "dGVzdF9zZWNyZXRfa2V5XzEyMzQ1".to_string(),
+                passphrase: "test_passphrase".to_string(),
+            })
+            .build()
+            .unwrap();
+        client.market_cache().set_tick_size("123", dec!(0.01));
+        client.market_cache().set_neg_risk("123", false);
+        client
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_refill_and_take() {
+        let client = test_client();
+        let pool = PresignedOrderPool::new(PresignConfig::default());
+        pool.add_ladder("123", Side::BUY, &[dec!(0.50), dec!(0.51)], &[dec!(10)]);
+
+        assert_eq!(pool.refill(&client).await.signed, 4);
+        assert_eq!(pool.refill(&client).await.signed, 0);
+
+        // Equal prices written differently share a key
+        let key = PresignKey::new("123", Side::BUY, dec!(0.5), dec!(10.0));
+        let first = pool.take(&key).unwrap();
+        assert_eq!(first.order.side, "BUY");
+        assert_eq!(first.order.maker_amount, "5000000");
+        assert_eq!(pool.available(&key), 1);
+        assert_ne!(pool.take(&key).unwrap().order.salt, first.order.salt);
+        assert!(pool.take(&key).is_none());
+        assert!(pool
+            .take(&PresignKey::new("123", Side::SELL, dec!(0.5), dec!(10)))
+            .is_none());
+
+        // A new nonce invalidates every order signed before it
+        assert_eq!(pool.set_nonce(U256::from(1)), 2);
+        assert_eq!(pool.refill(&client).await.signed, 4);
+        assert_eq!(pool.take(&key).unwrap().order.nonce, "1");
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_refill_signs_with_the_client_fee_rate() {
+        let client = ClobClient::builder("http://127.0.0.1:9")
+            .private_key("0x1234567890123456789012345678901234567890123456789012345678901234")
+            .fee_rate_bps(25)
+            .build()
+            .unwrap();
+        client.market_cache().set_tick_size("123", dec!(0.01));
+        client.market_cache().set_neg_risk("123", false);
+
+        let pool = PresignedOrderPool::new(PresignConfig {
+            depth: 1,
+            ..Default::default()
+        });
+        let key = PresignKey::new("123", Side::BUY, dec!(0.5), dec!(10));
+        pool.add(key.clone());
+        pool.set_nonce(U256::from(7));
+        assert_eq!(pool.refill(&client).await.signed, 1);
+
+        let order = pool.take(&key).unwrap().order;
+        assert_eq!(order.fee_rate_bps, "25");
+        assert_eq!(order.nonce, "7");
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_orders_close_to_lapsing_are_discarded() {
+        let client = test_client();
+        let pool = PresignedOrderPool::new(PresignConfig {
+            depth: 1,
+            order_type: OrderType::GTD,
+            expiration: Some(Duration::from_secs(300)),
+            min_lifetime: Duration::from_secs(60),
+            ..Default::default()
+        });
+        let key = PresignKey::new("123", Side::SELL, dec!(0.6), dec!(5));
+        pool.add(key.clone());
+        pool.refill(&client).await;
+
+        let order = pool.take(&key).unwrap();
+        let lapses_at = order.lapses_at().unwrap();
+        assert_eq!(order.order_type, OrderType::GTD);
+        assert!(lapses_at > Utc::now() + chrono::Duration::seconds(200));
+
+        pool.refill(&client).await;
+        assert_eq!(pool.discard_stale(Utc::now()), 0);
+        assert_eq!(pool.discard_stale(lapses_at), 1);
+        assert!(pool.take(&key).is_none());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_lapse_times_follow_the_shared_clock() {
+        let client = test_client();
+        let pool = PresignedOrderPool::new(PresignConfig {
+            depth: 1,
+            order_type: OrderType::GTD,
+            expiration: Some(Duration::from_secs(300)),
+            ..Default::default()
+        });
+        let key = PresignKey::new("123", Side::BUY, dec!(0.5), dec!(10));
+        pool.add(key.clone());
+        assert_eq!(pool.refill(&client).await.signed, 1);
+
+        // On an exchange an hour ahead the order has already lapsed
+        let clock = Arc::new(ClockSync::new());
+        let now = crate::utils::time::now_millis();
+        clock.record(crate::clock_sync::ClockSample {
+            sent_millis: now,
+            server_secs: now / 1000 + 3600,
+            received_millis: now,
+        });
+        let pool = pool.with_clock(clock);
+        assert!(pool.take(&key).is_none());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_refill_skips_keys_that_fail_to_sign() {
+        let client = test_client();
+        let pool = PresignedOrderPool::new(PresignConfig {
+            depth: 1,
+            ..Default::default()
+        });
+        let valid = PresignKey::new("123", Side::BUY, dec!(0.5), dec!(10));
+        let invalid = PresignKey::new("123", Side::BUY, dec!(1.5), dec!(10));
+        pool.add(valid.clone());
+        pool.add(invalid.clone());
+
+        let report = pool.refill(&client).await;
+        assert_eq!(report.signed, 1);
+        assert_eq!(report.failed.len(), 1);
+        assert_eq!(report.failed[0].0, invalid);
+        assert_eq!(pool.available(&valid), 1);
+        assert_eq!(pool.available(&invalid), 0);
+    }
+
+    #[test]
+    fn test_lifetime_cutoff_saturates() {
+        let pool = PresignedOrderPool::new(PresignConfig {
+            min_lifetime: Duration::from_secs(u64::MAX),
+            ..Default::default()
+        });
+        assert_eq!(pool.lifetime_cutoff(Utc::now()), DateTime::<Utc>::MAX_UTC);
+        assert_eq!(pool.discard_stale(Utc::now()), 0);
+    }
+}
@@ -4,11 +4,15 @@
 //! real-time market data and order updates.
 
 use crate::errors::{PolyfillError, Result};
+use crate::market_cache::MarketCache;
+use crate::metrics::ClientMetrics;
 use crate::types::*;
 use chrono::Utc;
 use futures::{SinkExt, Stream, StreamExt};
 use serde_json::Value;
 use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
 use std::task::{Context, Poll};
 use tokio::sync::mpsc;
 use tracing::{debug, error, info, warn};
@@ -28,9 +32,37 @@ pub trait MarketStream: Stream<Item = Result<StreamMessage>> + Send + Sync {
     fn get_stats(&self) -> StreamStats;
 }
 
+/// Shared record of when a stream last heard from the server
+///
+/// Clones share the same clock, so a health monitor can hold one while the
+/// stream itself is polled elsewhere.
+#[derive(Debug, Clone, Default)]
+pub struct StreamHeartbeat {
+    last_beat: Arc<AtomicU64>,
+}
+
+impl StreamHeartbeat {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record that the server was heard from just now
+    pub fn beat(&self) {
+        self.last_beat
+            .store(crate::utils::time::now_millis(), Ordering::Relaxed);
+    }
+
+    /// Unix millis of the last beat, if there has been one
+    pub fn last_beat_millis(&self) -> Option<u64> {
+        match self.last_beat.load(Ordering::Relaxed) {
+            0 => None,
+            millis => Some(millis),
+        }
+    }
+}
+
 /// WebSocket-based market stream implementation
 #[derive(Debug)]
-#[allow(dead_code)]
 pub struct WebSocketStream {
     /// WebSocket connection
     connection: Option<
@@ -52,6 +84,12 @@ pub struct WebSocketStream {
     stats: StreamStats,
     /// Reconnection configuration
     reconnect_config: ReconnectConfig,
+    /// Updated on every frame received from the server
+    heartbeat: StreamHeartbeat,
+    /// Kept current from `tick_size_change` events, if attached
+    market_cache: Option<Arc<MarketCache>>,
+    /// Metrics the stream reports its statistics into, and the name it uses
+    metrics: Option<(Arc<ClientMetrics>, String)>,
 }
 
 /// Stream statistics
@@ -106,15 +144,55 @@ impl WebSocketStream {
                 reconnect_count: 0,
             },
             reconnect_config: ReconnectConfig::default(),
+            heartbeat: StreamHeartbeat::new(),
+            market_cache: None,
+            metrics: None,
         }
     }
 
+    /// Heartbeat handle that tracks when this stream last received a frame
+    pub fn heartbeat(&self) -> StreamHeartbeat {
+        self.heartbeat.clone()
+    }
+
     /// Set authentication credentials
     pub fn with_auth(mut self, auth: WssAuth) -> Self {
         self.auth = Some(auth);
         self
     }
 
+    /// Apply tick size changes from this stream to a market cache
+    pub fn with_market_cache(mut self, market_cache: Arc<MarketCache>) -> Self {
+        self.market_cache = Some(market_cache);
+        self
+    }
+
+    /// Report this stream's statistics into `metrics` under `name`
+    pub fn with_metrics(mut self, metrics: Arc<ClientMetrics>, name: &str) -> Self {
+        self.metrics = Some((metrics, name.to_string()));
+        self
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Record a parsed message and hand it to attached observers
+    fn observe(&mut self, message: &StreamMessage) {
+        self.stats.messages_received += 1;
+        self.stats.last_message_time = Some(Utc::now());
+        if let Some(market_cache) = &self.market_cache {
+            market_cache.handle_stream_message(message);
+        }
+    }
+
+    /// Push the current statistics to the attached metrics
+    fn report_stats(&self) {
+        if let Some((metrics, name)) = &self.metrics {
+            metrics.record_stream(name, &self.stats);
+        }
+    }
+
     /// Connect to the WebSocket
     async fn connect(&mut self) -> Result<()> {
         let (ws_stream, _) = tokio_tungstenite::connect_async(&self.url)
@@ -147,6 +225,7 @@ impl WebSocketStream {
             })?;
 
             self.stats.messages_sent += 1;
+            self.report_stats();
         }
 
         Ok(())
@@ -270,20 +349,22 @@ impl WebSocketStream {
         &mut self,
         message: tokio_tungstenite::tungstenite::Message,
     ) -> Result<()> {
+        // Any frame, including pings and pongs, shows the server is alive
+        self.heartbeat.beat();
+
         match message {
             tokio_tungstenite::tungstenite::Message::Text(text) => {
                 debug!("Received WebSocket message: {}", text);
 
                 // Parse the message according to Polymarket's format
-                let stream_message = self.parse_polymarket_message(&text)?;
+                for stream_message in self.parse_polymarket_message(&text)? {
+                    self.observe(&stream_message);
 
-                // Send to internal channel
-                if let Err(e) = self.tx.send(stream_message) {
-                    error!("Failed to send message to internal channel: {}", e);
+                    // Send to internal channel
+                    if let Err(e) = self.tx.send(stream_message) {
+                        error!("Failed to send message to internal channel: {}", e);
+                    }
                 }
-
-                self.stats.messages_received += 1;
-                self.stats.last_message_time = Some(Utc::now());
             },
             tokio_tungstenite::tungstenite::Message::Close(_) => {
                 info!("WebSocket connection closed by server");
@@ -314,19 +395,35 @@ impl WebSocketStream {
     }
 
     /// Parse Polymarket WebSocket message format
-    #[allow(dead_code)]
-    fn parse_polymarket_message(&self, text: &str) -> Result<StreamMessage> {
-        let value: Value = serde_json::from_str(text).map_err(|e| {
-            PolyfillError::parse(
-                format!("Failed to parse WebSocket message: {}", e),
-                Some(Box::new(e)),
-            )
-        })?;
+    ///
+    /// A frame carries either a single event or, for book snapshots, an array
+    /// of events. Frames that are not JSON or name no known event are treated
+    /// as heartbeats, since they still show the connection is alive.
+    fn parse_polymarket_message(&self, text: &str) -> Result<Vec<StreamMessage>> {
+        let value: Value = match serde_json::from_str(text) {
+            Ok(value) => value,
+            Err(_) => {
+                debug!("Ignoring non-JSON WebSocket message: {}", text);
+                return Ok(vec![Self::heartbeat_message(&Value::Null)]);
+            },
+        };
 
-        // Extract message type
-        let message_type = value.get("type").and_then(|v| v.as_str()).ok_or_else(|| {
-            PolyfillError::parse("Missing 'type' field in WebSocket message", None)
-        })?;
+        match value {
+            Value::Array(events) if events.is_empty() => {
+                Ok(vec![Self::heartbeat_message(&Value::Null)])
+            },
+            Value::Array(events) => events.iter().map(Self::parse_event).collect(),
+            value => Ok(vec![Self::parse_event(&value)?]),
+        }
+    }
+
+    /// Parse a single event, dispatching on `event_type` and then `type`
+    fn parse_event(value: &Value) -> Result<StreamMessage> {
+        let message_type = value
+            .get("event_type")
+            .or_else(|| value.get("type"))
+            .and_then(|v| v.as_str())
+            .unwrap_or_default();
 
         match message_type {
             "book_update" => {
@@ -406,24 +503,35 @@ impl WebSocketStream {
                         })?;
                 Ok(StreamMessage::MarketTrade { data })
             },
-            "heartbeat" => {
-                let timestamp = value
-                    .get("timestamp")
-                    .and_then(|v| v.as_u64())
-                    .map(|ts| chrono::DateTime::from_timestamp(ts as i64, 0).unwrap_or_default())
-                    .unwrap_or_else(Utc::now);
-                Ok(StreamMessage::Heartbeat { timestamp })
+            "tick_size_change" => {
+                // Sent flat on the market channel rather than wrapped in `data`
+                let data = serde_json::from_value(value.get("data").unwrap_or(value).clone())
+                    .map_err(|e| {
+                        PolyfillError::parse(
+                            format!("Failed to parse tick size change: {}", e),
+                            Some(Box::new(e)),
+                        )
+                    })?;
+                Ok(StreamMessage::TickSizeChange { data })
             },
+            "heartbeat" => Ok(Self::heartbeat_message(value)),
             _ => {
-                warn!("Unknown message type: {}", message_type);
-                // Return heartbeat as fallback
-                Ok(StreamMessage::Heartbeat {
-                    timestamp: Utc::now(),
-                })
+                debug!("Ignoring unknown message type: {:?}", message_type);
+                Ok(Self::heartbeat_message(&Value::Null))
             },
         }
     }
 
+    /// Heartbeat stamped with the event's `timestamp`, or now if it has none
+    fn heartbeat_message(value: &Value) -> StreamMessage {
+        let timestamp = value
+            .get("timestamp")
+            .and_then(|v| v.as_u64())
+            .map(|ts| chrono::DateTime::from_timestamp(ts as i64, 0).unwrap_or_default())
+            .unwrap_or_else(Utc::now);
+        StreamMessage::Heartbeat { timestamp }
+    }
+
     /// Reconnect with exponential backoff
     #[allow(dead_code)]
     async fn reconnect(&mut self) -> Result<()> {
@@ -437,6 +545,7 @@ impl WebSocketStream {
                 Ok(()) => {
                     info!("Successfully reconnected");
                     self.stats.reconnect_count += 1;
+                    self.report_stats();
 
                     // Resubscribe to all previous subscriptions
                     let subscriptions = self.subscriptions.clone();
@@ -484,15 +593,42 @@ impl Stream for WebSocketStream {
         // Then check WebSocket connection
         if let Some(connection) = &mut self.connection {
             match connection.poll_next_unpin(cx) {
-                Poll::Ready(Some(Ok(_message))) => {
-                    // Simplified message handling
-                    Poll::Ready(Some(Ok(StreamMessage::Heartbeat {
-                        timestamp: Utc::now(),
-                    })))
+                Poll::Ready(Some(Ok(message))) => {
+                    self.heartbeat.beat();
+                    match message {
+                        tokio_tungstenite::tungstenite::Message::Text(text) => {
+                            let parsed = match self.parse_polymarket_message(&text) {
+                                Ok(messages) => {
+                                    for stream_message in &messages {
+                                        self.observe(stream_message);
+                                    }
+                                    let mut messages = messages.into_iter();
+                                    let first = messages.next();
+                                    // Later events of a snapshot are delivered on the next polls
+                                    for stream_message in messages {
+                                        let _ = self.tx.send(stream_message);
+                                    }
+                                    Ok(first
+                                        .unwrap_or_else(|| Self::heartbeat_message(&Value::Null)))
+                                },
+                                Err(e) => {
+                                    self.stats.errors += 1;
+                                    Err(e)
+                                },
+                            };
+                            self.report_stats();
+                            Poll::Ready(Some(parsed))
+                        },
+                        // Control frames only show the connection is alive
+                        _ => Poll::Ready(Some(Ok(StreamMessage::Heartbeat {
+                            timestamp: Utc::now(),
+                        }))),
+                    }
                 },
                 Poll::Ready(Some(Err(e))) => {
                     error!("WebSocket error: {}", e);
                     self.stats.errors += 1;
+                    self.report_stats();
                     Poll::Ready(Some(Err(e.into())))
                 },
                 Poll::Ready(None) => {
@@ -608,6 +744,8 @@ pub struct StreamManager {
     streams: Vec<Box<dyn MarketStream>>,
     message_tx: mpsc::UnboundedSender<StreamMessage>,
     message_rx: mpsc::UnboundedReceiver<StreamMessage>,
+    market_cache: Option<Arc<MarketCache>>,
+    metrics: Option<Arc<ClientMetrics>>,
 }
 
 impl Default for StreamManager {
@@ -624,6 +762,30 @@ impl StreamManager {
             streams: Vec::new(),
             message_tx,
             message_rx,
+            market_cache: None,
+            metrics: None,
+        }
+    }
+
+    /// Apply tick size changes from broadcast messages to a market cache
+    pub fn set_market_cache(&mut self, market_cache: Arc<MarketCache>) {
+        self.market_cache = Some(market_cache);
+    }
+
+    /// Report the statistics of every managed stream into `metrics`
+    ///
+    /// Streams are named `stream_0`, `stream_1`, ... in the order they were
+    /// added, and reported on every broadcast.
+    pub fn set_metrics(&mut self, metrics: Arc<ClientMetrics>) {
+        self.metrics = Some(metrics);
+    }
+
+    /// Push the current statistics of every managed stream to the metrics
+    pub fn report_stats(&self) {
+        if let Some(metrics) = &self.metrics {
+            for (index, stream) in self.streams.iter().enumerate() {
+                metrics.record_stream(&format!("stream_{}", index), &stream.get_stats());
+            }
         }
     }
 
@@ -640,6 +802,10 @@ impl StreamManager {
     }
 
     pub fn broadcast_message(&self, message: StreamMessage) -> Result<()> {
+        if let Some(market_cache) = &self.market_cache {
+            market_cache.handle_stream_message(&message);
+        }
+        self.report_stats();
         self.message_tx
             .send(message)
             .map_err(|e| PolyfillError::internal("Failed to broadcast message", e))
@@ -673,6 +839,101 @@ mod tests {
         assert_eq!(stream.get_stats().messages_received, 2);
     }
 
+    #[tokio::test]
+    async fn test_tick_size_change_reaches_attached_market_cache() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+
+        let server = tokio::spawn(async move {
+            let (socket, _) = listener.accept().await.unwrap();
+            let mut ws = tokio_tungstenite::accept_async(socket).await.unwrap();
+            // Wait for the subscription before publishing
+            ws.next().await.unwrap().unwrap();
+            let event = r#"{"event_type": "tick_size_change", "asset_id": "123",
+                "market": "0xm", "old_tick_size": "0.01", "new_tick_size": "0.001",
+                "side": "BUY", "timestamp": "1700000000000"}"#;
+            ws.send(tokio_tungstenite::tungstenite::Message::Text(
+                event.to_string(),
+            ))
+            .await
+            .unwrap();
+            ws
+        });
+
+        let cache = Arc::new(MarketCache::default());
+        cache.set_tick_size("123", rust_decimal_macros::dec!(0.01));
+
+        let metrics = Arc::new(ClientMetrics::new());
+        let mut stream = WebSocketStream::new(&url)
+            .with_market_cache(cache.clone())
+            .with_metrics(metrics.clone(), "market");
+        stream
+            .subscribe_market_channel(vec!["123".to_string()])
+            .await
+            .unwrap();
+
+        let message = stream.next().await.unwrap().unwrap();
+        assert!(matches!(message, StreamMessage::TickSizeChange { .. }));
+        assert_eq!(
+            cache.get("123").unwrap().tick_size,
+            Some(rust_decimal_macros::dec!(0.001))
+        );
+        assert_eq!(stream.get_stats().messages_received, 1);
+        assert_eq!(
+            metrics.snapshot(None).streams["market"].messages_received,
+            1
+        );
+
+        drop(server.await.unwrap());
+    }
+
+    #[test]
+    fn test_unrecognised_frames_parse_as_heartbeats() {
+        let stream = WebSocketStream::new("ws://127.0.0.1:0");
+
+        let snapshot = r#"[
+            {"event_type": "book", "asset_id": "123", "market": "0xm",
+             "bids": [], "asks": [], "timestamp": "1700000000000", "hash": "0x1"},
+            {"event_type": "tick_size_change", "asset_id": "123", "market": "0xm",
+             "old_tick_size": "0.01", "new_tick_size": "0.001"}
+        ]"#;
+        let messages = stream.parse_polymarket_message(snapshot).unwrap();
+        assert_eq!(messages.len(), 2);
+        assert!(matches!(messages[0], StreamMessage::Heartbeat { .. }));
+        assert!(matches!(messages[1], StreamMessage::TickSizeChange { .. }));
+
+        for frame in ["PONG", "[]", r#"{"event_type": "last_trade_price"}"#, "{}"] {
+            let messages = stream.parse_polymarket_message(frame).unwrap();
+            assert!(matches!(
+                messages.as_slice(),
+                [StreamMessage::Heartbeat { .. }]
+            ));
+        }
+    }
+
+    #[test]
+    fn test_stream_manager_updates_market_cache() {
+        let mut manager = StreamManager::new();
+        let cache = Arc::new(MarketCache::default());
+        manager.set_market_cache(cache.clone());
+
+        manager
+            .broadcast_message(StreamMessage::TickSizeChange {
+                data: TickSizeChange {
+                    asset_id: "123".to_string(),
+                    market: "0xm".to_string(),
+                    old_tick_size: rust_decimal_macros::dec!(0.01),
+                    new_tick_size: rust_decimal_macros::dec!(0.001),
+                },
+            })
+            .unwrap();
+
+        assert_eq!(
+            cache.get("123").unwrap().tick_size,
+            Some(rust_decimal_macros::dec!(0.001))
+        );
+    }
+
     #[test]
     fn test_stream_manager() {
         let mut manager = StreamManager::new();
@@ -685,4 +946,20 @@ mod tests {
         };
         assert!(manager.broadcast_message(message).is_ok());
     }
+
+    #[test]
+    fn test_stream_manager_reports_stream_metrics() {
+        let mut manager = StreamManager::new();
+        let mut mock_stream = MockStream::new();
+        mock_stream.add_error(PolyfillError::internal_simple("dropped frame"));
+        manager.add_stream(Box::new(mock_stream));
+        let metrics = Arc::new(ClientMetrics::new());
+        manager.set_metrics(metrics.clone());
+
+        let message = StreamMessage::Heartbeat {
+            timestamp: Utc::now(),
+        };
+        manager.broadcast_message(message).unwrap();
+        assert_eq!(metrics.snapshot(None).streams["stream_0"].errors, 1);
+    }
 }
@@ -211,9 +211,14 @@ impl Side {
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[allow(clippy::upper_case_acronyms)]
 pub enum OrderType {
+    /// Good till cancelled
     GTC,
+    /// Fill or kill: fill completely and immediately, or not at all
     FOK,
+    /// Good till date: rests until its expiration
     GTD,
+    /// Fill and kill: fill as much as possible immediately, cancel the rest
+    FAK,
 }
 
 impl OrderType {
@@ -222,10 +227,77 @@ impl OrderType {
             OrderType::GTC => "GTC",
             OrderType::FOK => "FOK",
             OrderType::GTD => "GTD",
+            OrderType::FAK => "FAK",
         }
     }
 }
 
+/// Lead time the exchange adds to GTD expirations
+///
+/// A GTD order stops resting one threshold before its signed expiration, and
+/// the exchange rejects expirations that are not at least this far ahead.
+pub const GTD_SECURITY_THRESHOLD: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// When a GTD order should leave the book
+///
+/// Both forms describe the time the order stops resting; the security
+/// threshold is added when the order is signed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OrderExpiration {
+    /// Expire this long after the order is created
+    After(std::time::Duration),
+    /// Expire at a fixed time
+    At(DateTime<Utc>),
+}
+
+impl OrderExpiration {
+    /// The time the order stops resting, relative to `now`
+    ///
+    /// Fails with `OrderErrorKind::InvalidExpiration` if an `After` duration
+    /// reaches past the latest representable time.
+    pub fn lapses_at(self, now: DateTime<Utc>) -> crate::errors::Result<DateTime<Utc>> {
+        match self {
+            OrderExpiration::After(duration) => chrono::Duration::from_std(duration)
+                .ok()
+                .and_then(|duration| now.checked_add_signed(duration))
+                .ok_or_else(|| {
+                    crate::errors::PolyfillError::order(
+                        format!("Order expiration {:?} from now is out of range", duration),
+                        crate::errors::OrderErrorKind::InvalidExpiration,
+                    )
+                }),
+            OrderExpiration::At(at) => Ok(at),
+        }
+    }
+
+    /// The Unix timestamp to sign into the order
+    ///
+    /// Fails with `OrderErrorKind::InvalidExpiration` if the order would
+    /// already have lapsed at `now`.
+    pub fn timestamp(self, now: DateTime<Utc>) -> crate::errors::Result<u64> {
+        let lapses_at = self.lapses_at(now)?;
+        if lapses_at <= now {
+            return Err(crate::errors::PolyfillError::order(
+                format!("Order expiration {} is not in the future", lapses_at),
+                crate::errors::OrderErrorKind::InvalidExpiration,
+            ));
+        }
+        Ok(lapses_at.timestamp() as u64 + GTD_SECURITY_THRESHOLD.as_secs())
+    }
+}
+
+impl From<std::time::Duration> for OrderExpiration {
+    fn from(duration: std::time::Duration) -> Self {
+        OrderExpiration::After(duration)
+    }
+}
+
+impl From<DateTime<Utc>> for OrderExpiration {
+    fn from(at: DateTime<Utc>) -> Self {
+        OrderExpiration::At(at)
+    }
+}
+
 /// Order status in the system
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum OrderStatus {
@@ -507,6 +579,19 @@ pub struct ExtraOrderArgs {
     pub fee_rate_bps: u32,
     pub nonce: U256,
     pub taker: String,
+    /// Fixed order salt; a random one is generated when `None`
+    ///
+    /// Signing the same order with the same salt yields the same order hash,
+    /// which is what makes resubmission idempotent.
+    pub salt: Option<u64>,
+}
+
+impl ExtraOrderArgs {
+    /// Derive the salt from a caller-chosen client order ID
+    pub fn with_client_order_id(mut self, client_order_id: &str) -> Self {
+        self.salt = Some(crate::orders::deterministic_salt(client_order_id));
+        self
+    }
 }
 
 impl Default for ExtraOrderArgs {
@@ -515,15 +600,62 @@ impl Default for ExtraOrderArgs {
             fee_rate_bps: 0,
             nonce: U256::ZERO,
             taker: "0x0000000000000000000000000000000000000000".to_string(),
+            salt: None,
         }
     }
 }
 
 /// Market order arguments
+///
+/// `amount` is denominated by side: collateral to spend for a BUY, shares to
+/// sell for a SELL. `worst_price` bounds the price walked from the book: the
+/// highest price a BUY accepts, the lowest a SELL accepts.
+///
+/// Build one with [`MarketOrderArgs::buy`], [`MarketOrderArgs::sell`] or
+/// [`MarketOrderArgs::new`]; fields may be added in future releases.
 #[derive(Debug, Clone)]
+#[non_exhaustive]
 pub struct MarketOrderArgs {
     pub token_id: String,
     pub amount: Decimal,
+    pub side: Side,
+    pub worst_price: Option<Decimal>,
+}
+
+impl MarketOrderArgs {
+    pub fn new(token_id: &str, amount: Decimal, side: Side) -> Self {
+        Self {
+            token_id: token_id.to_string(),
+            amount,
+            side,
+            worst_price: None,
+        }
+    }
+
+    /// Market BUY spending `amount` collateral
+    pub fn buy(token_id: &str, amount: Decimal) -> Self {
+        Self::new(token_id, amount, Side::BUY)
+    }
+
+    /// Market SELL of `shares`
+    pub fn sell(token_id: &str, shares: Decimal) -> Self {
+        Self::new(token_id, shares, Side::SELL)
+    }
+
+    /// Set the worst price the order may execute at
+    pub fn with_worst_price(mut self, worst_price: Decimal) -> Self {
+        self.worst_price = Some(worst_price);
+        self
+    }
+
+    /// Whether `price` is within this order's worst-price bound
+    pub fn accepts_price(&self, price: Decimal) -> bool {
+        match (self.worst_price, self.side) {
+            (None, _) => true,
+            (Some(limit), Side::BUY) => price <= limit,
+            (Some(limit), Side::SELL) => price >= limit,
+        }
+    }
 }
 
 /// Signed order request ready for submission
@@ -545,13 +677,29 @@ pub struct SignedOrderRequest {
     pub signature: String,
 }
 
+/// Placement options for posting signed orders
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PostOrderOptions {
+    /// Only rest on the book; the exchange rejects the order if it would take
+    pub post_only: bool,
+}
+
+impl PostOrderOptions {
+    /// Options for a post-only order
+    pub fn post_only() -> Self {
+        Self { post_only: true }
+    }
+}
+
 /// Post order wrapper
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct PostOrder {
     pub order: SignedOrderRequest,
     pub owner: String,
     pub order_type: OrderType,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub post_only: bool,
 }
 
 impl PostOrder {
@@ -560,22 +708,156 @@ impl PostOrder {
             order,
             owner,
             order_type,
+            post_only: false,
         }
     }
+
+    /// Ask the exchange to reject the order instead of letting it take liquidity
+    pub fn with_post_only(mut self, post_only: bool) -> Self {
+        self.post_only = post_only;
+        self
+    }
 }
 
 /// Post orders args for batch order submission
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct PostOrdersArgs {
     pub order: SignedOrderRequest,
     pub owner: String,
     pub order_type: OrderType,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub post_only: bool,
 }
 
 impl PostOrdersArgs {
     pub fn new(order: SignedOrderRequest, owner: String, order_type: OrderType) -> Self {
-        Self { order, owner, order_type }
+        Self {
+            order,
+            owner,
+            order_type,
+            post_only: false,
+        }
+    }
+
+    /// Ask the exchange to reject the order instead of letting it take liquidity
+    pub fn with_post_only(mut self, post_only: bool) -> Self {
+        self.post_only = post_only;
+        self
+    }
+}
+
+/// Placement status reported by the exchange for a posted order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PostOrderStatus {
+    /// Order is resting on the book
+    Live,
+    /// Order was matched (fully or partially) on arrival
+    Matched,
+    /// Order is marketable but its placement was delayed by the exchange
+    Delayed,
+    /// Order is marketable but was not matched after the delay
+    Unmatched,
+    /// Any status this client does not know about yet
+    #[serde(other)]
+    Unknown,
+}
+
+/// Response returned by `POST /order` and, per order, by `POST /orders`
+///
+/// The exchange answers 200 even when it rejects an order, so always check
+/// [`PostOrderResponse::error_kind`] (or call
+/// [`PostOrderResponse::into_result`]) before assuming the order is live.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PostOrderResponse {
+    #[serde(default)]
+    pub success: bool,
+    #[serde(rename = "errorMsg", default)]
+    pub error_msg: String,
+    #[serde(rename = "orderID", default)]
+    pub order_id: String,
+    #[serde(rename = "transactionsHashes", default)]
+    pub transaction_hashes: Vec<String>,
+    #[serde(default)]
+    pub status: Option<PostOrderStatus>,
+    #[serde(
+        rename = "takingAmount",
+        default,
+        deserialize_with = "crate::decode::deserializers::optional_number_from_string"
+    )]
+    pub taking_amount: Option<Decimal>,
+    #[serde(
+        rename = "makingAmount",
+        default,
+        deserialize_with = "crate::decode::deserializers::optional_number_from_string"
+    )]
+    pub making_amount: Option<Decimal>,
+}
+
+impl PostOrderResponse {
+    /// Classify a rejection reported inside a successful HTTP response
+    ///
+    /// Returns `None` when the exchange accepted the order.
+    pub fn error_kind(&self) -> Option<crate::errors::OrderErrorKind> {
+        if self.success && self.error_msg.is_empty() {
+            return None;
+        }
+        Some(crate::errors::OrderErrorKind::from_exchange_message(
+            &self.error_msg,
+        ))
+    }
+
+    /// Check whether the exchange accepted the order
+    pub fn is_accepted(&self) -> bool {
+        self.error_kind().is_none()
+    }
+
+    /// Convert an in-band rejection into a `PolyfillError::Order`
+    pub fn into_result(self) -> crate::errors::Result<Self> {
+        match self.error_kind() {
+            None => Ok(self),
+            Some(kind) => Err(crate::errors::PolyfillError::order(
+                format!("Order rejected by exchange: {}", self.error_msg),
+                kind,
+            )),
+        }
+    }
+}
+
+/// Response returned by the cancellation endpoints
+///
+/// `not_canceled` maps each order ID the exchange refused to cancel to the
+/// reason it gave.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CancelResponse {
+    #[serde(default)]
+    pub canceled: Vec<String>,
+    #[serde(default)]
+    pub not_canceled: std::collections::HashMap<String, String>,
+}
+
+impl CancelResponse {
+    /// Check whether a specific order was canceled
+    pub fn is_canceled(&self, order_id: &str) -> bool {
+        self.canceled.iter().any(|id| id == order_id)
+    }
+
+    /// Classify the reason an order was not canceled, if it was rejected
+    pub fn error_kind(&self, order_id: &str) -> Option<crate::errors::OrderErrorKind> {
+        self.not_canceled
+            .get(order_id)
+            .map(|reason| crate::errors::OrderErrorKind::from_cancel_reason(reason))
+    }
+
+    /// Iterate over every rejected cancellation with its classified reason
+    pub fn failures(&self) -> impl Iterator<Item = (&str, crate::errors::OrderErrorKind)> + '_ {
+        self.not_canceled.iter().map(|(id, reason)| {
+            (
+                id.as_str(),
+                crate::errors::OrderErrorKind::from_cancel_reason(reason),
+            )
+        })
     }
 }
 
@@ -649,9 +931,9 @@ pub struct ClientConfig {
     pub private_key: Option<String>,
     /// API credentials (optional)
     pub api_credentials: Option<ApiCredentials>,
-    /// Maximum slippage tolerance
+    /// Maximum slippage tolerance for market orders, in price units
     pub max_slippage: Option<Decimal>,
-    /// Fee rate in basis points
+    /// Default fee rate in basis points for new orders
     pub fee_rate: Option<Decimal>,
     /// Request timeout
     pub timeout: Option<std::time::Duration>,
@@ -736,6 +1018,22 @@ pub enum StreamMessage {
     MarketBookUpdate { data: OrderDelta },
     #[serde(rename = "market_trade")]
     MarketTrade { data: FillEvent },
+    #[serde(rename = "tick_size_change")]
+    TickSizeChange { data: TickSizeChange },
+}
+
+/// Tick size change published on the market channel
+///
+/// Sent when a market's price approaches the ends of the range and the
+/// exchange switches it to a finer (or back to a coarser) tick.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TickSizeChange {
+    pub asset_id: String,
+    pub market: String,
+    #[serde(with = "rust_decimal::serde::str")]
+    pub old_tick_size: Decimal,
+    #[serde(with = "rust_decimal::serde::str")]
+    pub new_tick_size: Decimal,
 }
 
 /// Subscription parameters for streaming
@@ -798,7 +1096,7 @@ pub type MarketId = String;
 pub type ClientId = String;
 
 /// Parameters for querying open orders
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct OpenOrderParams {
     pub id: Option<String>,
     pub asset_id: Option<String>,
@@ -825,7 +1123,7 @@ impl OpenOrderParams {
 }
 
 /// Parameters for querying trades
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct TradeParams {
     pub id: Option<String>,
     pub maker_address: Option<String>,
@@ -867,6 +1165,120 @@ impl TradeParams {
     }
 }
 
+/// Settlement status of a trade
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[allow(clippy::upper_case_acronyms)]
+pub enum TradeStatus {
+    /// Matched by the operator, not yet submitted on chain
+    MATCHED,
+    /// Included in a mined transaction
+    MINED,
+    /// Reached finality on chain
+    CONFIRMED,
+    /// Transaction failed and is being retried
+    RETRYING,
+    /// Transaction failed permanently
+    FAILED,
+    /// Status not known to this client
+    #[serde(other)]
+    Unknown,
+}
+
+impl TradeStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TradeStatus::MATCHED => "MATCHED",
+            TradeStatus::MINED => "MINED",
+            TradeStatus::CONFIRMED => "CONFIRMED",
+            TradeStatus::RETRYING => "RETRYING",
+            TradeStatus::FAILED => "FAILED",
+            TradeStatus::Unknown => "UNKNOWN",
+        }
+    }
+
+    /// Check whether the trade can no longer change state
+    pub fn is_final(&self) -> bool {
+        matches!(self, TradeStatus::CONFIRMED | TradeStatus::FAILED)
+    }
+}
+
+/// Which side of a trade the authenticated user was on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[allow(clippy::upper_case_acronyms)]
+pub enum TraderSide {
+    TAKER,
+    MAKER,
+}
+
+/// Maker order filled as part of a trade
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MakerOrder {
+    pub order_id: String,
+    pub owner: String,
+    pub maker_address: String,
+    #[serde(with = "rust_decimal::serde::str")]
+    pub matched_amount: Decimal,
+    #[serde(with = "rust_decimal::serde::str")]
+    pub price: Decimal,
+    #[serde(deserialize_with = "crate::decode::deserializers::number_from_string")]
+    pub fee_rate_bps: u32,
+    pub asset_id: String,
+    pub outcome: String,
+    #[serde(default)]
+    pub side: Option<Side>,
+}
+
+impl MakerOrder {
+    /// Fee charged on this maker's share of the trade, in collateral units
+    pub fn fee(&self) -> Decimal {
+        self.matched_amount * self.price * Decimal::from(self.fee_rate_bps) / Decimal::from(10_000)
+    }
+}
+
+/// Trade returned by `GET /data/trades`
+///
+/// A trade is one taker order matched against one or more maker orders.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Trade {
+    pub id: String,
+    pub taker_order_id: String,
+    pub market: String,
+    pub asset_id: String,
+    pub side: Side,
+    #[serde(with = "rust_decimal::serde::str")]
+    pub size: Decimal,
+    #[serde(deserialize_with = "crate::decode::deserializers::number_from_string")]
+    pub fee_rate_bps: u32,
+    #[serde(with = "rust_decimal::serde::str")]
+    pub price: Decimal,
+    pub status: TradeStatus,
+    #[serde(deserialize_with = "crate::decode::deserializers::number_from_string")]
+    pub match_time: u64,
+    #[serde(
+        default,
+        deserialize_with = "crate::decode::deserializers::optional_number_from_string"
+    )]
+    pub last_update: Option<u64>,
+    pub outcome: String,
+    #[serde(deserialize_with = "crate::decode::deserializers::number_from_string")]
+    pub bucket_index: u32,
+    pub owner: String,
+    pub maker_address: String,
+    #[serde(default)]
+    pub maker_orders: Vec<MakerOrder>,
+    #[serde(default)]
+    pub transaction_hash: Option<String>,
+    #[serde(default)]
+    pub trader_side: Option<TraderSide>,
+}
+
+impl Trade {
+    /// Fee charged on this trade, in collateral units
+    pub fn fee(&self) -> Decimal {
+        self.size * self.price * Decimal::from(self.fee_rate_bps) / Decimal::from(10_000)
+    }
+}
+
 /// Open order information
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OpenOrder {
@@ -904,7 +1316,7 @@ pub struct BalanceAllowance {
 }
 
 /// Parameters for balance allowance queries (from reference implementation)
-#[derive(Default)]
+#[derive(Debug, Clone, Default)]
 pub struct BalanceAllowanceParams {
     pub asset_type: Option<AssetType>,
     pub token_id: Option<String>,
@@ -935,6 +1347,7 @@ impl BalanceAllowanceParams {
 }
 
 /// Asset type enum for balance allowance queries
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[allow(clippy::upper_case_acronyms)]
 pub enum AssetType {
     COLLATERAL,
@@ -993,6 +1406,129 @@ pub struct BatchPriceResponse {
     pub prices: Vec<TokenPrice>,
 }
 
+/// Lookback window of a `/prices-history` query, ending now
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PriceHistoryInterval {
+    OneMinute,
+    OneHour,
+    SixHours,
+    OneDay,
+    OneWeek,
+    /// The market's whole history
+    Max,
+}
+
+impl PriceHistoryInterval {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PriceHistoryInterval::OneMinute => "1m",
+            PriceHistoryInterval::OneHour => "1h",
+            PriceHistoryInterval::SixHours => "6h",
+            PriceHistoryInterval::OneDay => "1d",
+            PriceHistoryInterval::OneWeek => "1w",
+            PriceHistoryInterval::Max => "max",
+        }
+    }
+}
+
+impl std::fmt::Display for PriceHistoryInterval {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for PriceHistoryInterval {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "1m" => Ok(PriceHistoryInterval::OneMinute),
+            "1h" => Ok(PriceHistoryInterval::OneHour),
+            "6h" => Ok(PriceHistoryInterval::SixHours),
+            "1d" => Ok(PriceHistoryInterval::OneDay),
+            "1w" => Ok(PriceHistoryInterval::OneWeek),
+            "max" => Ok(PriceHistoryInterval::Max),
+            _ => Err(format!("Unknown price history interval: {}", s)),
+        }
+    }
+}
+
+/// Time span of a `/prices-history` query
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PriceHistoryRange {
+    /// A window ending now
+    Interval(PriceHistoryInterval),
+    /// Unix seconds, both inclusive
+    Between { start: u64, end: u64 },
+}
+
+impl PriceHistoryRange {
+    pub fn between(start: u64, end: u64) -> Self {
+        PriceHistoryRange::Between { start, end }
+    }
+
+    pub fn to_query_params(self) -> Vec<(&'static str, String)> {
+        match self {
+            PriceHistoryRange::Interval(interval) => {
+                vec![("interval", interval.as_str().to_string())]
+            },
+            PriceHistoryRange::Between { start, end } => {
+                vec![("startTs", start.to_string()), ("endTs", end.to_string())]
+            },
+        }
+    }
+}
+
+impl From<PriceHistoryInterval> for PriceHistoryRange {
+    fn from(interval: PriceHistoryInterval) -> Self {
+        PriceHistoryRange::Interval(interval)
+    }
+}
+
+/// One sample of a token's price history
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PricePoint {
+    /// Unix seconds
+    pub timestamp: u64,
+    /// Price in ticks; zero when the token traded at nothing, e.g. after resolving
+    pub price: Price,
+}
+
+impl PricePoint {
+    pub fn price_decimal(&self) -> Decimal {
+        price_to_decimal(self.price)
+    }
+}
+
+/// `/prices-history` response, before conversion to [`PricePoint`]s
+#[derive(Debug, Deserialize)]
+pub struct PriceHistoryResponse {
+    pub history: Vec<PriceHistorySample>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PriceHistorySample {
+    pub t: u64,
+    pub p: Decimal,
+}
+
+impl PriceHistorySample {
+    /// The sampled price in ticks
+    ///
+    /// Unlike [`decimal_to_price`], a price of zero stays zero ticks instead
+    /// of being clamped up to the minimum tick.
+    pub fn price(&self) -> std::result::Result<Price, &'static str> {
+        let ticks = (self.p * Decimal::from(SCALE_FACTOR))
+            .round()
+            .to_u64()
+            .ok_or("Price too large or negative")?;
+        if ticks > MAX_PRICE_TICKS as u64 {
+            return Err("Price exceeds maximum");
+        }
+        Ok(ticks as Price)
+    }
+}
+
 // Additional types for API compatibility with reference implementation
 #[derive(Debug, Deserialize)]
 pub struct ApiKeysResponse {
@@ -349,7 +349,81 @@ pub mod retry {
         }
     }
 
+    /// Groups of endpoints that share a retry policy
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub enum EndpointClass {
+        /// Idempotent reads: market data, orders, trades, balances
+        Read,
+        /// Order placement (`/order`, `/orders`)
+        OrderPost,
+        /// Order cancellation
+        Cancel,
+        /// Other mutating endpoints: API keys, notifications, balance updates
+        Write,
+    }
+
+    /// Retry configuration per endpoint class
+    ///
+    /// `None` disables retries for that class. Only reads are retried by
+    /// default; retrying an order post can place the same order twice if the
+    /// first attempt reached the exchange, so it has to be opted into.
+    #[derive(Debug, Clone)]
+    pub struct RetryPolicy {
+        pub read: Option<RetryConfig>,
+        pub order_post: Option<RetryConfig>,
+        pub cancel: Option<RetryConfig>,
+        pub write: Option<RetryConfig>,
+    }
+
+    impl Default for RetryPolicy {
+        fn default() -> Self {
+            Self {
+                read: Some(RetryConfig::default()),
+                order_post: None,
+                cancel: None,
+                write: None,
+            }
+        }
+    }
+
+    impl RetryPolicy {
+        /// Policy that never retries
+        pub fn disabled() -> Self {
+            Self {
+                read: None,
+                order_post: None,
+                cancel: None,
+                write: None,
+            }
+        }
+
+        /// Set the retry configuration for one endpoint class
+        pub fn with_class(mut self, class: EndpointClass, config: Option<RetryConfig>) -> Self {
+            match class {
+                EndpointClass::Read => self.read = config,
+                EndpointClass::OrderPost => self.order_post = config,
+                EndpointClass::Cancel => self.cancel = config,
+                EndpointClass::Write => self.write = config,
+            }
+            self
+        }
+
+        /// Retry configuration for an endpoint class, if retries are enabled
+        pub fn config_for(&self, class: EndpointClass) -> Option<&RetryConfig> {
+            match class {
+                EndpointClass::Read => self.read.as_ref(),
+                EndpointClass::OrderPost => self.order_post.as_ref(),
+                EndpointClass::Cancel => self.cancel.as_ref(),
+                EndpointClass::Write => self.write.as_ref(),
+            }
+        }
+    }
+
     /// Retry a future with exponential backoff
+    ///
+    /// The wait before each retry is the larger of the backoff delay and the
+    /// error's own [`PolyfillError::retry_delay`] hint (e.g. a `Retry-After`),
+    /// never more than `max_delay`.
     pub async fn with_retry<F, Fut, T>(config: &RetryConfig, mut operation: F) -> Result<T>
     where
         F: FnMut() -> Fut,
@@ -368,13 +442,18 @@ pub mod retry {
                         return Err(err);
                     }
 
+                    let wait = err
+                        .retry_delay()
+                        .map_or(delay, |hint| hint.max(delay))
+                        .min(config.max_delay);
+
                     // Add jitter if enabled
                     let actual_delay = if config.jitter {
                         let jitter_factor = rand::random::<f64>() * 0.1; // ±10%
                         let jitter = 1.0 + (jitter_factor - 0.05);
-                        Duration::from_nanos((delay.as_nanos() as f64 * jitter) as u64)
+                        Duration::from_nanos((wait.as_nanos() as f64 * jitter) as u64)
                     } else {
-                        delay
+                        wait
                     };
 
                     sleep(actual_delay).await;
@@ -457,7 +536,9 @@ pub mod url {
 /// Rate limiting utilities
 pub mod rate_limit {
     use super::*;
+    use std::collections::HashMap;
     use std::sync::{Arc, Mutex};
+    use std::time::Instant;
 
     /// Simple token bucket rate limiter
     #[derive(Debug)]
@@ -491,6 +572,32 @@ pub mod rate_limit {
             }
         }
 
+        /// Wait until a token is available and consume it
+        ///
+        /// Returns how long the caller was throttled.
+        pub async fn acquire(&self) -> Duration {
+            if self.try_consume() {
+                return Duration::ZERO;
+            }
+
+            let start = Instant::now();
+            loop {
+                tokio::time::sleep(self.time_until_refill()).await;
+                if self.try_consume() {
+                    return start.elapsed();
+                }
+            }
+        }
+
+        /// Time until the next token is added
+        pub fn time_until_refill(&self) -> Duration {
+            let last_refill = *self.last_refill.lock().unwrap();
+            let elapsed = SystemTime::now()
+                .duration_since(last_refill)
+                .unwrap_or_default();
+            self.refill_rate.saturating_sub(elapsed)
+        }
+
         fn refill(&self) {
             let now = SystemTime::now();
             let mut last_refill = self.last_refill.lock().unwrap();
@@ -499,11 +606,182 @@ pub mod rate_limit {
             if elapsed >= self.refill_rate {
                 let tokens_to_add = elapsed.as_nanos() / self.refill_rate.as_nanos();
                 let mut tokens = self.tokens.lock().unwrap();
-                *tokens = std::cmp::min(self.capacity, *tokens + tokens_to_add as usize);
-                *last_refill = now;
+                if *tokens + tokens_to_add as usize >= self.capacity {
+                    *tokens = self.capacity;
+                    *last_refill = now;
+                } else {
+                    // Keep the partial interval so slow refill rates don't drift
+                    *tokens += tokens_to_add as usize;
+                    *last_refill += self.refill_rate * tokens_to_add as u32;
+                }
             }
         }
     }
+
+    /// Groups of endpoints that share a rate limit on the exchange
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub enum EndpointGroup {
+        /// `GET /book`
+        Book,
+        /// `POST /books`
+        Books,
+        /// Single-token pricing: `/price`, `/midpoint`, `/spread`, `/last-trade-price`
+        Price,
+        /// Batch pricing: `/prices`, `/midpoints`, `/spreads`, `/last-trades-prices`
+        Prices,
+        /// `POST /order`
+        Order,
+        /// `POST /orders`
+        Orders,
+        /// `DELETE /order`
+        Cancel,
+        /// `DELETE /orders` and `DELETE /cancel-market-orders`
+        CancelBatch,
+        /// `DELETE /cancel-all`
+        CancelAll,
+        /// `/data/orders`, `/data/order/{id}`, `/data/trades`
+        Data,
+        /// `/markets`, `/simplified-markets` and the sampling variants
+        Markets,
+        /// Everything else
+        General,
+    }
+
+    impl EndpointGroup {
+        /// Classify a request by method and URL path
+        pub fn classify(method: &str, path: &str) -> Self {
+            match (method, path) {
+                ("POST", "/order") => Self::Order,
+                ("DELETE", "/order") => Self::Cancel,
+                ("POST", "/orders") => Self::Orders,
+                ("DELETE", "/orders") | (_, "/cancel-market-orders") => Self::CancelBatch,
+                (_, "/cancel-all") => Self::CancelAll,
+                (_, "/book") => Self::Book,
+                (_, "/books") => Self::Books,
+                (_, "/price" | "/midpoint" | "/spread" | "/last-trade-price") => Self::Price,
+                (_, "/prices" | "/midpoints" | "/spreads" | "/last-trades-prices") => Self::Prices,
+                (_, p) if p.starts_with("/data/") => Self::Data,
+                (_, p)
+                    if p.starts_with("/markets")
+                        || p.starts_with("/simplified-markets")
+                        || p.starts_with("/sampling-") =>
+                {
+                    Self::Markets
+                },
+                _ => Self::General,
+            }
+        }
+    }
+
+    /// Time spent waiting for tokens in one endpoint group
+    #[derive(Debug, Clone, Default, PartialEq, Eq)]
+    pub struct ThrottleStats {
+        /// Tokens acquired
+        pub acquired: u64,
+        /// Acquisitions that had to wait
+        pub throttled: u64,
+        /// Total time spent waiting
+        pub total_wait: Duration,
+        /// Longest single wait
+        pub max_wait: Duration,
+    }
+
+    /// Per-endpoint-group token buckets
+    ///
+    /// Requests wait for a token from their group's bucket before they are
+    /// sent, so bursts are smoothed out client side instead of being answered
+    /// with 429s. Groups without their own bucket use the `General` bucket;
+    /// if that is not configured either, they are not limited.
+    #[derive(Debug, Default)]
+    pub struct RateLimiter {
+        buckets: HashMap<EndpointGroup, TokenBucket>,
+        stats: Mutex<HashMap<EndpointGroup, ThrottleStats>>,
+    }
+
+    impl RateLimiter {
+        /// Limiter without any buckets
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        /// Limits matching the published CLOB burst limits (per 10 seconds)
+        pub fn polymarket_defaults() -> Self {
+            Self::new()
+                .with_limit(EndpointGroup::General, 5000, 500)
+                .with_limit(EndpointGroup::Book, 200, 20)
+                .with_limit(EndpointGroup::Books, 80, 8)
+                .with_limit(EndpointGroup::Price, 200, 20)
+                .with_limit(EndpointGroup::Prices, 80, 8)
+                .with_limit(EndpointGroup::Order, 2400, 240)
+                .with_limit(EndpointGroup::Orders, 800, 80)
+                .with_limit(EndpointGroup::Cancel, 2400, 240)
+                .with_limit(EndpointGroup::CancelBatch, 800, 80)
+                .with_limit(EndpointGroup::CancelAll, 20, 2)
+                .with_limit(EndpointGroup::Data, 150, 15)
+                .with_limit(EndpointGroup::Markets, 250, 25)
+        }
+
+        /// Set the bucket for an endpoint group
+        pub fn with_limit(
+            mut self,
+            group: EndpointGroup,
+            capacity: usize,
+            refill_per_second: usize,
+        ) -> Self {
+            self.buckets
+                .insert(group, TokenBucket::new(capacity, refill_per_second));
+            self
+        }
+
+        /// Wait for a token in `group`, returning how long the caller was throttled
+        pub async fn acquire(&self, group: EndpointGroup) -> Duration {
+            let bucket = self
+                .buckets
+                .get(&group)
+                .or_else(|| self.buckets.get(&EndpointGroup::General));
+
+            let waited = match bucket {
+                Some(bucket) => bucket.acquire().await,
+                None => Duration::ZERO,
+            };
+
+            let mut stats = self.stats.lock().unwrap();
+            let entry = stats.entry(group).or_default();
+            entry.acquired += 1;
+            if !waited.is_zero() {
+                entry.throttled += 1;
+                entry.total_wait += waited;
+                entry.max_wait = entry.max_wait.max(waited);
+            }
+
+            waited
+        }
+
+        /// Throttling stats for one endpoint group
+        pub fn stats(&self, group: EndpointGroup) -> ThrottleStats {
+            self.stats
+                .lock()
+                .unwrap()
+                .get(&group)
+                .cloned()
+                .unwrap_or_default()
+        }
+
+        /// Throttling stats for every group that has seen a request
+        pub fn all_stats(&self) -> HashMap<EndpointGroup, ThrottleStats> {
+            self.stats.lock().unwrap().clone()
+        }
+
+        /// Total time spent throttled across all groups
+        pub fn total_wait(&self) -> Duration {
+            self.stats
+                .lock()
+                .unwrap()
+                .values()
+                .map(|s| s.total_wait)
+                .sum()
+        }
+    }
 }
 
 #[cfg(test)]
@@ -542,6 +820,121 @@ mod tests {
         assert_eq!(back, amount);
     }
 
+    #[test]
+    fn test_retry_policy_defaults() {
+        use retry::{EndpointClass, RetryConfig, RetryPolicy};
+
+        let policy = RetryPolicy::default();
+        assert!(policy.config_for(EndpointClass::Read).is_some());
+        assert!(policy.config_for(EndpointClass::OrderPost).is_none());
+        assert!(policy.config_for(EndpointClass::Cancel).is_none());
+
+        let policy = policy.with_class(EndpointClass::Cancel, Some(RetryConfig::default()));
+        assert!(policy.config_for(EndpointClass::Cancel).is_some());
+        assert!(RetryPolicy::disabled()
+            .config_for(EndpointClass::Read)
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_with_retry_stops_on_non_retryable_error() {
+        use retry::{with_retry, RetryConfig};
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let attempts = AtomicUsize::new(0);
+        let result: Result<()> = with_retry(&RetryConfig::default(), || async {
+            attempts.fetch_add(1, Ordering::SeqCst);
+            Err(PolyfillError::api(400, "bad request"))
+        })
+        .await;
+
+        assert!(result.is_err());
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_with_retry_caps_retry_after_at_max_delay() {
+        use retry::{with_retry, RetryConfig};
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let config = RetryConfig {
+            max_attempts: 2,
+            initial_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(10),
+            backoff_factor: 2.0,
+            jitter: false,
+        };
+        let attempts = AtomicUsize::new(0);
+        let result: Result<()> = tokio::time::timeout(
+            Duration::from_secs(5),
+            with_retry(&config, || async {
+                attempts.fetch_add(1, Ordering::SeqCst);
+                Err(PolyfillError::from_response(
+                    429,
+                    "Rate limited",
+                    "",
+                    Some(Duration::from_secs(3600)),
+                ))
+            }),
+        )
+        .await
+        .expect("Retry-After hint should be capped at max_delay");
+
+        assert!(result.is_err());
+        assert_eq!(attempts.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_endpoint_group_classification() {
+        use rate_limit::EndpointGroup;
+
+        assert_eq!(
+            EndpointGroup::classify("POST", "/order"),
+            EndpointGroup::Order
+        );
+        assert_eq!(
+            EndpointGroup::classify("DELETE", "/order"),
+            EndpointGroup::Cancel
+        );
+        assert_eq!(
+            EndpointGroup::classify("DELETE", "/orders"),
+            EndpointGroup::CancelBatch
+        );
+        assert_eq!(EndpointGroup::classify("GET", "/book"), EndpointGroup::Book);
+        assert_eq!(
+            EndpointGroup::classify("GET", "/data/order/0x1"),
+            EndpointGroup::Data
+        );
+        assert_eq!(
+            EndpointGroup::classify("GET", "/markets/0xabc"),
+            EndpointGroup::Markets
+        );
+        assert_eq!(
+            EndpointGroup::classify("GET", "/time"),
+            EndpointGroup::General
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rate_limiter_waits_for_token() {
+        use rate_limit::{EndpointGroup, RateLimiter};
+
+        let limiter = RateLimiter::new().with_limit(EndpointGroup::Book, 1, 50);
+
+        assert_eq!(limiter.acquire(EndpointGroup::Book).await, Duration::ZERO);
+        let waited = limiter.acquire(EndpointGroup::Book).await;
+        assert!(waited > Duration::ZERO);
+
+        // Unconfigured groups are not limited
+        assert_eq!(limiter.acquire(EndpointGroup::Order).await, Duration::ZERO);
+
+        let stats = limiter.stats(EndpointGroup::Book);
+        assert_eq!(stats.acquired, 2);
+        assert_eq!(stats.throttled, 1);
+        assert_eq!(stats.total_wait, waited);
+        assert_eq!(limiter.total_wait(), waited);
+    }
+
     #[test]
     fn test_address_validation() {
         use address::parse_address;
@@ -99,6 +99,7 @@ async fn test_real_api_authenticated_order_flow() {
         price: order_price,
         size: dec!(1.0), // Minimum size
         side: Side::BUY,
+        post_only: false,
     };
 
     let post_result = client.create_and_post_order(&order_args).await;
@@ -109,7 +110,8 @@ async fn test_real_api_authenticated_order_flow() {
             println!("PASS: Order posted successfully!");
 
             // Step 5: Cancel the order
-            if let Some(order_id) = response.get("orderID").and_then(|v| v.as_str()) {
+            if !response.order_id.is_empty() {
+                let order_id = &response.order_id;
                 println!("Step 5: Canceling order {}...", order_id);
                 let cancel_result = client.cancel(order_id).await;
                 assert!(
@@ -0,0 +1,134 @@
+// End-to-end trading against the in-process mock exchange
+#![cfg(feature = "mock-server")]
+
+use polyfill_rs::errors::OrderErrorKind;
+use polyfill_rs::types::{OpenOrderParams, PostOrderOptions, PostOrderStatus};
+use polyfill_rs::{
+    ApiCredentials, ClobClient, MockClob, MockMarket, OrderArgs, OrderType, PolyfillError, Side,
+};
+use rust_decimal_macros::dec;
+
+const MAKER_KEY: &str = "0x1111111111111111111111111111111111111111111111111111111111111111";
+const TAKER_KEY: &str = "0x2222222222222222222222222222222222222222222222222222222222222222";
+const TOKEN_ID: &str = "123";
+
+async fn start_exchange() -> MockClob {
+    let exchange = MockClob::start().await.unwrap();
+    exchange.add_market(MockMarket::new("0xcondition", TOKEN_ID));
+    exchange
+}
+
+async fn trader(exchange: &MockClob, private_key: &str) -> (ClobClient, ApiCredentials) {
+    let mut client = ClobClient::with_l1_headers(exchange.url(), private_key, 137);
+    let creds = client.create_or_derive_api_key(None).await.unwrap();
+    client.set_api_creds(creds.clone());
+    (client, creds)
+}
+
+fn order_kind(error: PolyfillError) -> OrderErrorKind {
+    match error {
+        PolyfillError::Order { kind, .. } => kind,
+        other => panic!("expected order error, got {:?}", other),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_orders_match_and_cancel() {
+    let exchange = start_exchange().await;
+    let (maker, maker_creds) = trader(&exchange, MAKER_KEY).await;
+    let (taker, _) = trader(&exchange, TAKER_KEY).await;
+
+    // Deriving again returns the key created above
+    let derived = maker.derive_api_key(None).await.unwrap();
+    assert_eq!(derived.api_key, maker_creds.api_key);
+
+    let ask = OrderArgs::new(TOKEN_ID, dec!(0.55), dec!(10), Side::SELL);
+    let resting = maker.create_and_post_order(&ask).await.unwrap();
+    assert_eq!(resting.status, Some(PostOrderStatus::Live));
+
+    let book = taker.get_order_book(TOKEN_ID).await.unwrap();
+    assert_eq!(book.asks.len(), 1);
+    assert_eq!(book.asks[0].price, dec!(0.55));
+
+    // Crosses the ask and fills at the resting price
+    let bid = OrderArgs::new(TOKEN_ID, dec!(0.56), dec!(4), Side::BUY);
+    let taken = taker.create_and_post_order(&bid).await.unwrap();
+    assert_eq!(taken.status, Some(PostOrderStatus::Matched));
+    assert_eq!(taken.taking_amount, Some(dec!(4)));
+    assert_eq!(taken.making_amount, Some(dec!(2.20)));
+
+    let trades = taker.get_trades(None, None).await.unwrap();
+    assert_eq!(trades.len(), 1);
+    assert_eq!(trades[0].price, dec!(0.55));
+    assert_eq!(trades[0].maker_orders[0].order_id, resting.order_id);
+    assert_eq!(maker.get_trades(None, None).await.unwrap().len(), 1);
+
+    let open = maker
+        .get_orders(Some(&OpenOrderParams::default()), None)
+        .await
+        .unwrap();
+    assert_eq!(open.len(), 1);
+    assert_eq!(open[0].size_matched, dec!(4));
+    assert!(taker.get_orders(None, None).await.unwrap().is_empty());
+
+    // Post-only and FOK orders that cannot be honored are rejected
+    let post_only = OrderArgs::new(TOKEN_ID, dec!(0.55), dec!(1), Side::BUY).with_post_only(true);
+    let order = taker
+        .create_order(&post_only, None, None, None)
+        .await
+        .unwrap();
+    let error = taker
+        .post_order_with_options(order, OrderType::GTC, PostOrderOptions::post_only())
+        .await
+        .unwrap()
+        .into_result()
+        .unwrap_err();
+    assert_eq!(order_kind(error), OrderErrorKind::WouldCross);
+
+    let too_big = OrderArgs::new(TOKEN_ID, dec!(0.55), dec!(100), Side::BUY);
+    let order = taker
+        .create_order(&too_big, None, None, None)
+        .await
+        .unwrap();
+    let error = taker
+        .post_order(order, OrderType::FOK)
+        .await
+        .unwrap()
+        .into_result()
+        .unwrap_err();
+    assert_eq!(order_kind(error), OrderErrorKind::NotFilled);
+
+    let cancel = maker.cancel_all().await.unwrap();
+    assert!(cancel.is_canceled(&resting.order_id));
+    assert!(exchange.open_orders().is_empty());
+    assert!(taker
+        .get_order_book(TOKEN_ID)
+        .await
+        .unwrap()
+        .asks
+        .is_empty());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_requests_with_bad_credentials_are_rejected() {
+    let exchange = start_exchange().await;
+    let (mut client, mut creds) = trader(&exchange, MAKER_KEY).await;
+    creds.secret = "b3RoZXJfc2VjcmV0".to_string();
+    client.set_api_creds(creds);
+    match client.get_orders(None, None).await {
+        Err(PolyfillError::Auth { .. }) => {},
+        other => panic!("expected auth error, got {:?}", other),
+    }
+
+    // Credentials registered up front work without an L1 round trip
+    let mut client = ClobClient::with_l1_headers(exchange.url(), TAKER_KEY, 137);
+    let address = client.get_address().unwrap().parse().unwrap();
+    let creds = ApiCredentials {
+        api_key: "test_key".to_string(),
+        secret: "dGVzdF9zZWNyZXRfa2V5XzEyMzQ1".to_string(),
+        passphrase: "test_passphrase".to_string(),
+    };
+    exchange.add_api_key(address, creds.clone());
+    client.set_api_creds(creds);
+    assert!(client.get_orders(None, None).await.unwrap().is_empty());
+}
@@ -31,6 +31,7 @@ async fn test_post_order_authentication() {
         price: Decimal::from_str("0.01").unwrap(), // Very low price, won't fill
         size: Decimal::from_str("1.0").unwrap(),
         side: Side::BUY,
+        post_only: false,
     };
 
     let result = client.create_and_post_order(&order_args).await;
@@ -41,9 +42,9 @@ async fn test_post_order_authentication() {
             println!("  Response: {:?}", response);
 
             // Try to cancel it if we got an order ID
-            if let Some(order_id) = response.get("orderID").and_then(|v| v.as_str()) {
+            if !response.order_id.is_empty() {
                 println!("\nStep 3: Canceling order...");
-                match client.cancel(order_id).await {
+                match client.cancel(&response.order_id).await {
                     Ok(_) => println!("Order canceled successfully"),
                     Err(e) => println!("Cancel failed (order might have expired): {:?}", e),
                 }